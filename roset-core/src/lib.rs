@@ -0,0 +1,104 @@
+//! Runtime traits implemented by roset derives, so generic code can be written over any roset enum
+
+#![no_std]
+
+/// Name of the current variant as declared in the `enum`
+pub trait VariantName {
+    /// Returns the variant name, e.g. `"Cat"` for `Animal::Cat`
+    fn variant_name(&self) -> &'static str;
+}
+
+impl<T: VariantName + ?Sized> VariantName for &T {
+    fn variant_name(&self) -> &'static str {
+        T::variant_name(self)
+    }
+}
+
+/// Number of variants in the `enum`
+pub trait EnumCount {
+    /// Count of declared variants
+    const COUNT: usize;
+}
+
+/// Two-way mapping between the `enum` and its string representation
+pub trait StrRepr: Sized {
+    /// Returns the string of the current variant
+    fn as_str(&self) -> &str;
+
+    /// Returns the variant matching the string, if any
+    fn from_str_repr(s: &str) -> Option<Self>;
+}
+
+/// String mapping of `enum` whose strings are all static, unlike a catch-all variant borrowing
+/// from its payload
+pub trait StaticStr {
+    /// Returns the static string of the variant, an associated function so it is never shadowed
+    /// by a method of a smart pointer holding the value
+    fn static_str(this: &Self) -> &'static str;
+}
+
+impl<T: StaticStr + ?Sized> StaticStr for &T {
+    fn static_str(this: &Self) -> &'static str {
+        T::static_str(this)
+    }
+}
+
+/// Metadata of one variant, listed in declaration order by the generated `METADATA` table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VariantMeta {
+    /// Variant name as declared
+    pub name: &'static str,
+    /// Static string of the variant, `None` without one or when it borrows from the payload
+    pub str_value: Option<&'static str>,
+    /// Position of the variant in the declaration
+    pub ordinal: usize,
+    /// Whether the variant has fields
+    pub has_payload: bool,
+}
+
+/// Support of generated code, not public API
+#[doc(hidden)]
+pub mod __private {
+    /// Whether both strs have the same bytes, `==` of `str` is not const
+    pub const fn str_eq(a: &str, b: &str) -> bool {
+        let (a, b) = (a.as_bytes(), b.as_bytes());
+        if a.len() != b.len() {
+            return false;
+        }
+        let mut i = 0;
+        while i < a.len() {
+            if a[i] != b[i] {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    /// Length of the value written as lowercase hex with `0x` prefix, and `-` if `negative`
+    pub const fn hex_len(negative: bool, mut magnitude: u128) -> usize {
+        let mut len = 3 + negative as usize;
+        while magnitude > 0xf {
+            magnitude >>= 4;
+            len += 1;
+        }
+        len
+    }
+
+    /// The value written as lowercase hex with `0x` prefix, `N` is given by `hex_len`
+    pub const fn hex_bytes<const N: usize>(negative: bool, mut magnitude: u128) -> [u8; N] {
+        let mut bytes = [b'0'; N];
+        let start = negative as usize;
+        if negative {
+            bytes[0] = b'-';
+        }
+        bytes[start + 1] = b'x';
+        let mut i = N;
+        while i > start + 2 {
+            i -= 1;
+            bytes[i] = b"0123456789abcdef"[(magnitude & 0xf) as usize];
+            magnitude >>= 4;
+        }
+        bytes
+    }
+}
@@ -0,0 +1,180 @@
+use proc_macro2::Span;
+use syn::ext::IdentExt;
+use syn::parse::Parse;
+use syn::parse::ParseStream;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::Attribute;
+use syn::Error;
+use syn::Expr;
+use syn::ExprLit;
+use syn::Ident;
+use syn::Lit;
+use syn::LitInt;
+use syn::LitStr;
+use syn::Result;
+use syn::Token;
+
+/// One item of a roset attribute: `key`, `key = value` or `key(a, b = value..)`
+#[derive(Clone)]
+pub(crate) struct AttrItem {
+    pub key: Ident,
+    pub value: AttrValue,
+}
+
+#[derive(Clone)]
+pub(crate) enum AttrValue {
+    None,
+    Expr(Box<Expr>),
+    List(Vec<AttrItem>),
+}
+
+impl Parse for AttrItem {
+    fn parse(input: ParseStream) -> Result<Self> {
+        // keywords like `type` are valid keys
+        let key = Ident::parse_any(input)?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            AttrValue::Expr(Box::new(input.parse()?))
+        } else if input.peek(syn::token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+            let items = Punctuated::<AttrItem, Comma>::parse_terminated(&content)?;
+            AttrValue::List(items.into_iter().collect())
+        } else {
+            AttrValue::None
+        };
+
+        Ok(AttrItem { key, value })
+    }
+}
+
+impl AttrItem {
+    /// Same item under another key, for attribute aliases
+    pub fn renamed(&self, key: &str) -> AttrItem {
+        AttrItem {
+            key: Ident::new(key, self.key.span()),
+            value: self.value.clone(),
+        }
+    }
+
+    pub fn is(&self, key: &str) -> bool {
+        self.key == key
+    }
+
+    pub fn span(&self) -> Span {
+        self.key.span()
+    }
+
+    fn error(&self, expected: &str) -> Error {
+        Error::new(self.span(), format!("`{}` expected {}", self.key, expected))
+    }
+
+    /// Item without value, like `inner`
+    pub fn flag(&self) -> Result<()> {
+        match self.value {
+            AttrValue::None => Ok(()),
+            _ => Err(self.error("no value")),
+        }
+    }
+
+    pub fn expr(&self) -> Result<&Expr> {
+        match &self.value {
+            AttrValue::Expr(expr) => Ok(expr),
+            _ => Err(self.error("a value like `key = value`")),
+        }
+    }
+
+    /// Nested items, like `renamed(A = "B")`
+    pub fn list(&self) -> Result<&[AttrItem]> {
+        match &self.value {
+            AttrValue::List(items) => Ok(items),
+            _ => Err(self.error("a list like `key(a, b = value)`")),
+        }
+    }
+
+    pub fn lit(&self) -> Result<&Lit> {
+        let mut expr = self.expr()?;
+        // `macro_rules` fragments like `$s:literal` arrive wrapped in an invisible group
+        while let Expr::Group(group) = expr {
+            expr = &group.expr;
+        }
+        match expr {
+            Expr::Lit(ExprLit { lit, .. }) => Ok(lit),
+            _ => Err(self.error("a literal value")),
+        }
+    }
+
+    pub fn str_value(&self) -> Result<&LitStr> {
+        match self.lit()? {
+            Lit::Str(lit) => Ok(lit),
+            _ => Err(self.error("a literal string value")),
+        }
+    }
+
+    pub fn int_value(&self) -> Result<&LitInt> {
+        match self.lit()? {
+            Lit::Int(lit) => Ok(lit),
+            _ => Err(self.error("an integer literal value")),
+        }
+    }
+}
+
+fn check_supported(name: &str, item: &AttrItem, supported: &[&str]) -> Result<()> {
+    if supported.iter().any(|key| item.is(key)) {
+        return Ok(());
+    }
+
+    Err(Error::new(
+        item.span(),
+        format!(
+            "unknown {} attribute `{}`, supported: {}",
+            name,
+            item.key,
+            supported.join(", ")
+        ),
+    ))
+}
+
+/// Whether the attribute is `name`, rustc expands `cfg_attr` before derives so wrapped ones
+/// arrive as plain attributes
+pub(crate) fn is_attr(attr: &Attribute, name: &str) -> bool {
+    let segments = &attr.path.segments;
+    segments.len() == 1 && segments[0].ident == name && segments[0].arguments.is_empty()
+}
+
+/// Parse items of every `name(a, b..)` attribute, rejecting keys not in `supported`
+pub(crate) fn parse_items(
+    name: &str,
+    attrs: &[Attribute],
+    supported: &[&str],
+) -> Result<Vec<AttrItem>> {
+    let items = parse_all_items(name, attrs)?;
+    for item in items.iter() {
+        check_supported(name, item, supported)?;
+    }
+    Ok(items)
+}
+
+/// Parse items of every `name(a, b..)` attribute with any key, for attributes owned by
+/// another crate
+pub(crate) fn parse_all_items(name: &str, attrs: &[Attribute]) -> Result<Vec<AttrItem>> {
+    let mut items = vec![];
+    for attr in attrs.iter() {
+        if !is_attr(attr, name) {
+            continue;
+        }
+
+        let parsed = attr
+            .parse_args_with(Punctuated::<AttrItem, Comma>::parse_terminated)
+            .map_err(|err| {
+                Error::new(
+                    err.span(),
+                    format!("{}, expected {}(a, b = \"c\"..)", err, name),
+                )
+            })?;
+
+        items.extend(parsed);
+    }
+    Ok(items)
+}
@@ -0,0 +1,75 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::DataEnum;
+use syn::Generics;
+use syn::Ident;
+use syn::Visibility;
+
+pub(crate) struct EnumArity {
+    vis: Visibility,
+    enum_name: Ident,
+    generics: Generics,
+    enum_data: DataEnum,
+}
+
+impl EnumArity {
+    pub fn new(vis: Visibility, enum_name: Ident, generics: Generics, enum_data: DataEnum) -> Self {
+        EnumArity {
+            vis,
+            enum_name,
+            generics,
+            enum_data,
+        }
+    }
+
+    /// `arity` with per variant field counts and names in declaration order
+    pub fn write_output(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        let count = self.enum_data.variants.len();
+
+        let variants: Vec<_> = self
+            .enum_data
+            .variants
+            .iter()
+            .map(|var| &var.ident)
+            .collect();
+        let arities: Vec<_> = self
+            .enum_data
+            .variants
+            .iter()
+            .map(|var| var.fields.len())
+            .collect();
+        // tuple fields are named by position like in `self.0`
+        let names = self.enum_data.variants.iter().map(|var| {
+            let names = var
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| match &field.ident {
+                    Some(ident) => ident.to_string(),
+                    None => i.to_string(),
+                });
+            quote! { &[#(#names),*] }
+        });
+
+        quote! {
+            #[allow(deprecated)]
+            impl #impl_generics #enum_name #ty_generics #where_clause {
+                /// Field count of every variant in declaration order
+                #vis const ARITIES: [usize; #count] = [#(#arities),*];
+
+                /// Field names of every variant in declaration order, tuple fields by position
+                #vis const FIELD_NAMES: [&'static [&'static str]; #count] = [#(#names),*];
+
+                /// Returns the field count of the variant
+                #vis const fn arity(&self) -> usize {
+                    match *self {
+                        #(Self::#variants { .. } => #arities,)*
+                    }
+                }
+            }
+        }
+    }
+}
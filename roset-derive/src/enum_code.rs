@@ -0,0 +1,135 @@
+use crate::attr::parse_items;
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::Attribute;
+use syn::DataEnum;
+use syn::Error;
+use syn::Fields;
+use syn::Ident;
+use syn::LitInt;
+use syn::Result;
+use syn::Visibility;
+
+pub(crate) struct EnumCode {
+    vis: Visibility,
+    enum_name: Ident,
+    enum_attrs: Vec<Attribute>,
+    enum_data: DataEnum,
+
+    code_type: Ident,
+    codes: Vec<(Ident, LitInt, String)>,
+}
+
+impl EnumCode {
+    pub fn new(
+        vis: Visibility,
+        enum_name: Ident,
+        enum_attrs: Vec<Attribute>,
+        enum_data: DataEnum,
+    ) -> Self {
+        EnumCode {
+            vis,
+            enum_name,
+            enum_attrs,
+            enum_data,
+            code_type: format_ident!("u16"),
+            codes: vec![],
+        }
+    }
+
+    pub fn parse_attributes(&mut self) -> Result<()> {
+        for item in parse_items("enum_code", &self.enum_attrs, &["code_type"])? {
+            self.code_type = item.str_value()?.parse()?;
+        }
+
+        let variants = self.enum_data.variants.clone();
+        for var in variants.iter() {
+            if !matches!(var.fields, Fields::Unit) {
+                let reason = format!("{}: EnumCode only support unit variant", self.enum_name);
+                return Err(Error::new(var.ident.span(), reason));
+            }
+
+            let (mut code, mut message) = (None, None);
+            for item in parse_items("enum_code", &var.attrs, &["code", "message"])? {
+                match item.key.to_string().as_str() {
+                    "code" => code = Some(item.int_value()?.clone()),
+                    "message" => message = Some(item.str_value()?.value()),
+                    _ => unreachable!(),
+                }
+            }
+
+            let code = match code {
+                Some(code) => code,
+                None => {
+                    let reason = format!(
+                        "{}::{}: enum_code(code) attribute is required",
+                        self.enum_name, var.ident
+                    );
+                    return Err(Error::new(var.ident.span(), reason));
+                }
+            };
+            let message = message.unwrap_or_else(|| var.ident.to_string());
+            let digits = code.base10_digits().to_string();
+            if let Some((other, _, _)) = self
+                .codes
+                .iter()
+                .find(|(_, lit, _)| lit.base10_digits() == digits)
+            {
+                let reason = format!("code {} already used by {}", digits, other);
+                return Err(Error::new(code.span(), reason));
+            }
+            self.codes.push((var.ident.clone(), code, message));
+        }
+        Ok(())
+    }
+
+    pub fn write_output(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let code_type = &self.code_type;
+
+        let to_code = self.codes.iter().map(|(var, code, _)| {
+            quote! { #enum_name::#var => #code }
+        });
+        let to_message = self.codes.iter().map(|(var, _, message)| {
+            quote! { #enum_name::#var => #message }
+        });
+        let from_code = self.codes.iter().map(|(var, code, _)| {
+            quote! { #code => Some(#enum_name::#var) }
+        });
+
+        quote! {
+            #[allow(deprecated)]
+            impl #enum_name {
+                /// Returns the code declared by `enum_code` of the variant
+                #vis fn code(&self) -> #code_type {
+                    match self {
+                        #(#to_code,)*
+                    }
+                }
+
+                /// Returns the message declared by `enum_code` of the variant
+                #vis fn message(&self) -> &'static str {
+                    match self {
+                        #(#to_message,)*
+                    }
+                }
+
+                /// Returns the variant declaring the code, if any
+                #vis fn from_code(code: #code_type) -> Option<Self> {
+                    match code {
+                        #(#from_code,)*
+                        _ => None,
+                    }
+                }
+            }
+
+            impl ::core::fmt::Display for #enum_name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.write_str(self.message())
+                }
+            }
+        }
+    }
+}
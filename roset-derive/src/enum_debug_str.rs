@@ -0,0 +1,71 @@
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::DataEnum;
+use syn::Fields;
+use syn::Generics;
+use syn::Ident;
+
+pub(crate) struct EnumDebugStr {
+    enum_name: Ident,
+    generics: Generics,
+    enum_data: DataEnum,
+}
+
+impl EnumDebugStr {
+    pub fn new(enum_name: Ident, generics: Generics, enum_data: DataEnum) -> Self {
+        EnumDebugStr {
+            enum_name,
+            generics,
+            enum_data,
+        }
+    }
+
+    /// `Debug` writing the str of unit variants, payload variants are formatted like derived
+    pub fn write_output(&self) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+
+        let arms = self.enum_data.variants.iter().map(|var| {
+            let var_name = &var.ident;
+            let name = var_name.to_string();
+            match &var.fields {
+                Fields::Unit => quote! {
+                    #enum_name::#var_name => f.pad(::roset::StrRepr::as_str(self))
+                },
+                Fields::Unnamed(fields) => {
+                    let bindings: Vec<_> = (0..fields.unnamed.len())
+                        .map(|i| format_ident!("field{}", i))
+                        .collect();
+                    quote! {
+                        #enum_name::#var_name(#(#bindings),*) => f
+                            .debug_tuple(#name)
+                            #(.field(#bindings))*
+                            .finish()
+                    }
+                }
+                Fields::Named(fields) => {
+                    let names: Vec<_> = fields.named.iter().map(|field| &field.ident).collect();
+                    let labels = names.iter().map(|name| name.as_ref().unwrap().to_string());
+                    quote! {
+                        #enum_name::#var_name { #(#names),* } => f
+                            .debug_struct(#name)
+                            #(.field(#labels, #names))*
+                            .finish()
+                    }
+                }
+            }
+        });
+
+        quote! {
+            #[allow(deprecated)]
+            impl #impl_generics ::core::fmt::Debug for #enum_name #ty_generics #where_clause {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match self {
+                        #(#arms,)*
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,2944 @@
+use crate::attr::parse_all_items;
+use crate::attr::parse_items;
+use crate::attr::AttrItem;
+use crate::attr::AttrValue;
+use crate::options::RosetOptions;
+use crate::to_snake_case;
+use proc_macro2::Span;
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use quote::quote_spanned;
+use syn::Attribute;
+use syn::DataEnum;
+use syn::Error;
+use syn::Expr;
+use syn::ExprPath;
+use syn::Fields;
+use syn::Ident;
+use syn::LitByteStr;
+use syn::Result;
+use syn::Type;
+use syn::Variant;
+use syn::Visibility;
+
+const ENUM_ATTRIBUTES: &[&str] = &[
+    "os_str",
+    "bytes",
+    "accept_ordinal",
+    "accessor",
+    "error_owns_input",
+    "strict",
+    "partial",
+    "flags",
+    "strum_compat",
+    "rename_all",
+    "strip_prefix",
+    "prefix",
+    "suffix",
+    "json",
+    "toml",
+    "yaml",
+    "http",
+    "serde_as_str",
+    "strict_roundtrip",
+    "allow_prefix_overlap",
+    "str_ids",
+    "namespace",
+    "error_msg",
+    "eq_str",
+    "serde_tag",
+    "serde_content",
+    "defmt",
+    "strategy",
+    "smol_str",
+    "compact_str",
+    "web",
+    "ts",
+    "async_graphql",
+    "with_matcher",
+];
+
+/// `strategy = "auto"` looks strings up in a table from this many str values on
+const AUTO_TABLE_SIZE: usize = 32;
+
+const VARIANT_ATTRIBUTES: &[&str] = &[
+    "str",
+    "inner",
+    "inner_from",
+    "default",
+    "other",
+    "flatten",
+    "hidden",
+];
+
+const FIELD_ATTRIBUTES: &[&str] = &["skip_field"];
+
+/// How the generated parse error keeps the rejected input
+enum ErrorInput {
+    Discard,
+    Owned,
+    Buffer(usize),
+}
+
+/// Piece of the `error_msg` template, placeholders are filled when the error is displayed
+enum MessagePart {
+    Text(String),
+    Input,
+    Expected,
+}
+
+pub(crate) struct EnumFrom {
+    vis: Visibility,
+    enum_name: Ident,
+    enum_attrs: Vec<Attribute>,
+    enum_data: DataEnum,
+    variants: Vec<Ident>,
+
+    /// `(variant, str)` in variant declaration order
+    from_str: Vec<(Ident, String)>,
+    /// Variants declaring `str = CONST`, their `from_str` entry holds the path for messages
+    str_consts: Vec<(Ident, ExprPath)>,
+    /// `(variant, str)` of extra `strum(serialize = "..")`, accepted by `FromStr` only
+    str_aliases: Vec<(Ident, String)>,
+    from_inner_enum: Vec<Ident>,
+    /// `(variant, source)` of `inner_from = "i8"`, widened into the payload by `Into`
+    inner_from: Vec<(Ident, Type)>,
+    /// Field indexes of inner variants filled with `Default` instead of the `From` source
+    skip_fields: Vec<(Ident, Vec<usize>)>,
+    default_variant: Option<Ident>,
+    other_variant: Option<Ident>,
+    flatten: Vec<(Ident, Type)>,
+    /// Function like `fn(&str) -> Option<Self>` trying input not found in the str mapping
+    matcher: Option<ExprPath>,
+    /// Variants parsed but left out of `VARIANT_STRS`, `METADATA`, `all()` and `{expected}`
+    hidden: Vec<Ident>,
+    from_os_str: bool,
+    from_bytes: bool,
+    accept_ordinal: bool,
+    accessors: Vec<Ident>,
+    error_input: ErrorInput,
+    partial: bool,
+    flags: bool,
+    strum_compat: bool,
+    sort_str_match: bool,
+    gen_tests: Option<Span>,
+    /// Name of the sealed trait replacing the roset-core trait impls, with `roset(sealed)`
+    sealed_name: Option<Ident>,
+    /// Emit `ROSET_MANIFEST`, a JSON description of the variants for build tooling
+    manifest: bool,
+    rename_all: Option<String>,
+    strip_prefix: Option<String>,
+    prefix: String,
+    suffix: String,
+    /// Enabled conversions to and from value types of data format crates, like `json`
+    value_formats: Vec<String>,
+    /// Enabled conversions to and from string types of other crates, like `smol_str`
+    string_types: Vec<String>,
+    http: bool,
+    defmt: bool,
+    serde_as_str: bool,
+    /// `Deserialize` from the str for path and query parameters of web frameworks
+    web: bool,
+    /// `ts_union` and `json_schema` describing the str values for other languages
+    ts: bool,
+    /// async-graphql `InputType` and `OutputType` with the strs as GraphQL names
+    async_graphql: bool,
+    /// Field names of the serde tagged representation keyed by the str values
+    serde_tag: Option<String>,
+    serde_content: Option<String>,
+    /// Require `to_str` and `FromStr` to be inverse of each other
+    strict_roundtrip: bool,
+    /// Silence the warning about a str being the prefix of another
+    allow_prefix_overlap: bool,
+    /// Number the str values, `FromStr` then goes through the ids
+    str_ids: bool,
+    /// Compare the enum with strings through the first accessor
+    eq_str: bool,
+    /// Written before every str with a dot, like `net.timeout`
+    namespace: Option<String>,
+    /// Parsing strategy as written, resolved to `table` once the str values are known
+    strategy: String,
+    /// Binary search a sorted table of str values instead of matching them
+    table: bool,
+    /// Names of the generated types, `{Enum}ParseError` and `{Enum}Set` unless renamed
+    error_name: Ident,
+    set_name: Ident,
+    /// Display of the not found parse error
+    error_msg: Option<Vec<MessagePart>>,
+}
+
+impl EnumFrom {
+    pub fn new(
+        vis: Visibility,
+        enum_name: Ident,
+        enum_attrs: Vec<Attribute>,
+        enum_data: DataEnum,
+    ) -> Self {
+        EnumFrom {
+            error_name: format_ident!("{}ParseError", enum_name),
+            set_name: format_ident!("{}Set", enum_name),
+            vis,
+            enum_name,
+            enum_attrs,
+            enum_data,
+            variants: vec![],
+            from_str: vec![],
+            str_consts: vec![],
+            str_aliases: vec![],
+            from_inner_enum: vec![],
+            inner_from: vec![],
+            skip_fields: vec![],
+            default_variant: None,
+            other_variant: None,
+            flatten: vec![],
+            matcher: None,
+            hidden: vec![],
+            from_os_str: false,
+            from_bytes: false,
+            accept_ordinal: false,
+            accessors: vec![],
+            error_input: ErrorInput::Discard,
+            partial: false,
+            flags: false,
+            strum_compat: false,
+            sort_str_match: false,
+            gen_tests: None,
+            sealed_name: None,
+            manifest: false,
+            rename_all: None,
+            strip_prefix: None,
+            prefix: String::new(),
+            suffix: String::new(),
+            value_formats: vec![],
+            string_types: vec![],
+            http: false,
+            defmt: false,
+            serde_as_str: false,
+            web: false,
+            ts: false,
+            async_graphql: false,
+            serde_tag: None,
+            serde_content: None,
+            strict_roundtrip: false,
+            allow_prefix_overlap: false,
+            str_ids: false,
+            eq_str: false,
+            namespace: None,
+            error_msg: None,
+            strategy: "match".to_string(),
+            table: false,
+        }
+    }
+
+    pub fn parse_attributes(&mut self) -> Result<()> {
+        self.parse_enum_attributes()?;
+        self.parse_variant_attributes()?;
+        self.check_str_options()?;
+        self.check_strict_roundtrip()?;
+        self.resolve_strategy()?;
+        self.check_conversions()
+    }
+
+    fn resolve_strategy(&mut self) -> Result<()> {
+        self.table = match self.strategy.as_str() {
+            "auto" => self.from_str.len() >= AUTO_TABLE_SIZE,
+            strategy => strategy == "table",
+        };
+        let conflict = match self.table {
+            true if self.str_ids => Some("str_ids, which matches through str_id_of"),
+            true if self.sort_str_match => Some("roset(sort_str_match), which sorts match arms"),
+            _ => None,
+        };
+        if let (Some(conflict), false) = (conflict, self.strategy == "auto") {
+            let reason = format!(
+                "enum_from(strategy = \"table\") can not be combined with {}",
+                conflict
+            );
+            return Err(Error::new(self.enum_name.span(), reason));
+        }
+        // auto keeps matching when the table conflicts, catch-all variants need no table
+        self.table &= conflict.is_none() && !self.from_str.is_empty() && self.str_consts.is_empty();
+        Ok(())
+    }
+
+    /// Options generating code from the str mapping, checked before any output is written
+    fn check_str_options(&self) -> Result<()> {
+        let enum_name = &self.enum_name;
+        let title = format!("EnumFrom for {}", enum_name);
+        let fail = |span, reason: String| Err(Error::new(span, format!("{}: {}", title, reason)));
+        let vars_from_str =
+            self.from_str.len() + self.other_variant.iter().count() + self.flatten.len();
+        if vars_from_str == 0 {
+            let required = [
+                ("os_str", self.from_os_str),
+                ("bytes", self.from_bytes),
+                ("accept_ordinal", self.accept_ordinal),
+                ("partial", self.partial),
+                ("flags", self.flags),
+                ("gen_tests", self.gen_tests.is_some()),
+                ("http", self.http),
+                ("defmt", self.defmt),
+                ("str_ids", self.str_ids),
+                ("serde_as_str", self.serde_as_str),
+                ("eq_str", self.eq_str),
+                ("serde_tag", self.serde_tag.is_some()),
+                ("web", self.web),
+                ("ts", self.ts),
+                ("async_graphql", self.async_graphql),
+            ];
+            let formats = self.value_formats.iter().chain(self.string_types.iter());
+            let used = required
+                .iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(name, _)| *name)
+                .chain(formats.map(String::as_str))
+                .next();
+            if let Some(name) = used {
+                let reason = format!("{} attribute requires enum_from(str) on variants", name);
+                return fail(enum_name.span(), reason);
+            }
+        } else if !self.partial && vars_from_str != self.variants.len() {
+            // a str behind an inactive cfg_attr never reaches the derive
+            let missing: Vec<_> = self
+                .variants
+                .iter()
+                .filter(|var| {
+                    !self.has_str(var)
+                        && self.other_variant.as_ref() != Some(*var)
+                        && !self.flatten.iter().any(|(other, _)| other.eq(*var))
+                })
+                .collect();
+            let names: Vec<_> = missing.iter().map(|var| var.to_string()).collect();
+            let reason = format!(
+                "enum_from(str) attribute must be used for all variants, \
+                 or use enum_from(partial) on the enum, missing on {} \
+                 (str inside an inactive cfg_attr counts as missing)",
+                names.join(", ")
+            );
+            return fail(missing[0].span(), reason);
+        }
+
+        if let Some(other) = &self.other_variant {
+            let var = self
+                .enum_data
+                .variants
+                .iter()
+                .find(|var| var.ident.eq(other));
+            if self.has_str(other) {
+                return fail(
+                    other.span(),
+                    format!("other attribute on {} can not be used with str", other),
+                );
+            }
+            if self.default_variant.is_some() {
+                return fail(
+                    other.span(),
+                    format!("other attribute on {} can not be used with default", other),
+                );
+            }
+            if !matches!(var.map(|var| &var.fields), Some(Fields::Unnamed(fields)) if fields.unnamed.len() == 1)
+            {
+                let reason = format!(
+                    "other attribute on {} requires a variant like Other(String)",
+                    other
+                );
+                return fail(other.span(), reason);
+            }
+        }
+        if let Some(var) = self
+            .enum_data
+            .variants
+            .iter()
+            .find(|var| var.fields.is_empty() && self.from_inner_enum.contains(&var.ident))
+        {
+            let reason = format!(
+                "inner attribute on {} requires a variant with fields",
+                var.ident
+            );
+            return fail(var.ident.span(), reason);
+        }
+
+        // every str is known and static, a catch-all or flattened variant has none
+        let all_static = !self.partial && self.other_variant.is_none() && self.flatten.is_empty();
+        let static_required = [
+            ("ts", self.ts),
+            ("async_graphql", self.async_graphql),
+            ("serde_tag", self.serde_tag.is_some()),
+            ("str_ids", self.str_ids),
+            ("http", self.http),
+            ("defmt", self.defmt),
+        ];
+        if let (Some((name, _)), false) =
+            (static_required.iter().find(|(_, used)| *used), all_static)
+        {
+            let reason = format!("{} attribute requires static str on all variants", name);
+            return fail(enum_name.span(), reason);
+        }
+        if self.flags && (self.partial || self.other_variant.is_some()) {
+            return fail(
+                enum_name.span(),
+                "flags attribute requires str on all variants".to_string(),
+            );
+        }
+        if let Some(string_type) = self.string_types.first().filter(|_| self.partial) {
+            let reason = format!("{} attribute requires str on all variants", string_type);
+            return fail(enum_name.span(), reason);
+        }
+        if self.flags && self.variants.len() > 128 {
+            return fail(
+                enum_name.span(),
+                "flags attribute supports at most 128 variants".to_string(),
+            );
+        }
+        for var in self.enum_data.variants.iter() {
+            let name = &var.ident;
+            if self.flags && !matches!(var.fields, Fields::Unit) {
+                return fail(
+                    name.span(),
+                    format!("flags attribute requires unit variant, found {}", name),
+                );
+            }
+            if self.async_graphql && !var.fields.is_empty() {
+                return fail(
+                    name.span(),
+                    "async_graphql attribute only supports unit variants".to_string(),
+                );
+            }
+            let tuple = matches!(&var.fields, Fields::Unnamed(fields) if fields.unnamed.len() > 1);
+            if self.serde_tag.is_some() && self.serde_content.is_none() && tuple {
+                let reason = format!(
+                    "serde_tag without serde_content can not tag tuple variant {}",
+                    name
+                );
+                return fail(name.span(), reason);
+            }
+        }
+        for (var, value) in self
+            .from_str
+            .iter()
+            .filter(|(var, _)| !self.is_str_const(var))
+        {
+            if self.from_bytes && !value.is_ascii() {
+                let reason = format!(
+                    "bytes attribute requires ASCII str value, found {:?} on {}",
+                    value, var
+                );
+                return fail(var.span(), reason);
+            }
+            // the same bytes `HeaderValue::from_static` accepts
+            if self.http && !value.bytes().all(|b| b == b'\t' || (32..127).contains(&b)) {
+                return fail(
+                    var.span(),
+                    format!("str {:?} of {} is not a valid header value", value, var),
+                );
+            }
+            let mut chars = value.chars();
+            let graphql_name = chars
+                .next()
+                .is_some_and(|c| c == '_' || c.is_ascii_alphabetic())
+                && chars.all(|c| c == '_' || c.is_ascii_alphanumeric());
+            if self.async_graphql && !graphql_name {
+                let reason = format!(
+                    "str {:?} of {} is not a GraphQL name like [_A-Za-z][_0-9A-Za-z]*",
+                    value, var
+                );
+                return fail(var.span(), reason);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject every mapping where a str does not parse back to the same value or a value has no str
+    fn check_strict_roundtrip(&self) -> Result<()> {
+        if !self.strict_roundtrip {
+            return Ok(());
+        }
+
+        let enum_name = &self.enum_name;
+        let fail = |span, reason: &str| {
+            let reason = format!("{}: strict_roundtrip {}", enum_name, reason);
+            Err(Error::new(span, reason))
+        };
+        if self.partial {
+            return fail(
+                enum_name.span(),
+                "requires str on all variants, remove partial",
+            );
+        }
+        if self.accept_ordinal {
+            return fail(
+                enum_name.span(),
+                "forbids accept_ordinal, ordinals parse to another str",
+            );
+        }
+        if let Some(default) = &self.default_variant {
+            return fail(
+                default.span(),
+                "forbids default, unknown strings parse to it",
+            );
+        }
+        if let Some(other) = &self.other_variant {
+            return fail(
+                other.span(),
+                "forbids other, it may hold the str of another variant",
+            );
+        }
+        if let Some((var_name, _)) = self.flatten.first() {
+            return fail(
+                var_name.span(),
+                "forbids flatten, inner strings can not be checked",
+            );
+        }
+        if self.matcher.is_some() {
+            return fail(
+                enum_name.span(),
+                "forbids with_matcher, matched strings parse to another str",
+            );
+        }
+        for var in self.enum_data.variants.iter() {
+            if !var.fields.is_empty() {
+                return fail(
+                    var.ident.span(),
+                    "forbids payloads, parsing fills them with Default",
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Report every source type converted by more than one generated impl in one error,
+    /// `From<T>` counts as `TryFrom<T>` through the blanket impl
+    fn check_conversions(&self) -> Result<()> {
+        let mut table: Vec<(String, String)> = vec![];
+        for var in self.enum_data.variants.iter() {
+            if self.from_inner_enum.contains(&var.ident) && !var.fields.is_empty() {
+                let origin = format!("From by enum_from(inner) on {}", var.ident);
+                table.push((crate::type_key(&self.inner_payload(var).0), origin));
+            }
+        }
+        for (var_name, source) in self.inner_from.iter() {
+            let origin = format!("From by enum_from(inner_from) on {}", var_name);
+            table.push((crate::type_key(&quote! { #source }), origin));
+        }
+        if self.from_bytes {
+            let origin = "TryFrom by enum_from(bytes)".to_string();
+            table.push((crate::type_key(&quote! { &[u8] }), origin));
+        }
+        if self.from_os_str {
+            for os_str in [quote! { &OsStr }, quote! { &std::ffi::OsStr }] {
+                let origin = "TryFrom by enum_from(os_str)".to_string();
+                table.push((crate::type_key(&os_str), origin));
+            }
+        }
+
+        let mut overlaps = vec![];
+        for (i, (key, _)) in table.iter().enumerate() {
+            if table[..i].iter().any(|(seen, _)| seen.eq(key)) {
+                continue;
+            }
+            let origins: Vec<_> = table
+                .iter()
+                .filter(|(other, _)| other.eq(key))
+                .map(|(_, origin)| origin.as_str())
+                .collect();
+            if origins.len() > 1 {
+                overlaps.push(format!("{}: {}", key, origins.join(", ")));
+            }
+        }
+
+        if overlaps.is_empty() {
+            return Ok(());
+        }
+        let reason = format!(
+            "EnumFrom for {} generates overlapping conversions from {}",
+            self.enum_name,
+            overlaps.join("; ")
+        );
+        Err(Error::new(self.enum_name.span(), reason))
+    }
+
+    fn parse_enum_attributes(&mut self) -> Result<()> {
+        for item in parse_items("enum_from", &self.enum_attrs, ENUM_ATTRIBUTES)? {
+            match item.key.to_string().as_str() {
+                "os_str" => item.flag().map(|_| self.from_os_str = true)?,
+                "bytes" => item.flag().map(|_| self.from_bytes = true)?,
+                "accept_ordinal" => item.flag().map(|_| self.accept_ordinal = true)?,
+                "strict" => item.flag().map(|_| self.partial = false)?,
+                "strict_roundtrip" => item.flag().map(|_| self.strict_roundtrip = true)?,
+                "allow_prefix_overlap" => item.flag().map(|_| self.allow_prefix_overlap = true)?,
+                "str_ids" => item.flag().map(|_| self.str_ids = true)?,
+                "eq_str" => item.flag().map(|_| self.eq_str = true)?,
+                "partial" => item.flag().map(|_| self.partial = true)?,
+                "flags" => item.flag().map(|_| self.flags = true)?,
+                "strum_compat" => item.flag().map(|_| self.strum_compat = true)?,
+                "accessor" => self.accessors.push(item.str_value()?.parse()?),
+                "rename_all" => {
+                    let case = item.str_value()?;
+                    if !crate::RENAME_CASES.contains(&case.value().as_str()) {
+                        let reason = format!(
+                            "unknown rename_all case {:?}, supported: {}",
+                            case.value(),
+                            crate::RENAME_CASES.join(", ")
+                        );
+                        return Err(Error::new(case.span(), reason));
+                    }
+                    self.rename_all = Some(case.value());
+                }
+                "strip_prefix" => self.strip_prefix = Some(item.str_value()?.value()),
+                format @ ("json" | "toml" | "yaml") => {
+                    item.flag()?;
+                    let enabled = match format {
+                        "json" => cfg!(feature = "json"),
+                        "toml" => cfg!(feature = "toml"),
+                        _ => cfg!(feature = "yaml"),
+                    };
+                    if !enabled {
+                        let reason =
+                            format!("enum_from({0}) requires the `{0}` feature of roset", format);
+                        return Err(Error::new(item.span(), reason));
+                    }
+                    self.value_formats.push(format.to_string());
+                }
+                string_type @ ("smol_str" | "compact_str") => {
+                    item.flag()?;
+                    let enabled = match string_type {
+                        "smol_str" => cfg!(feature = "smol_str"),
+                        _ => cfg!(feature = "compact_str"),
+                    };
+                    if !enabled {
+                        let reason = format!(
+                            "enum_from({0}) requires the `{0}` feature of roset",
+                            string_type
+                        );
+                        return Err(Error::new(item.span(), reason));
+                    }
+                    self.string_types.push(string_type.to_string());
+                }
+                "defmt" => {
+                    item.flag()?;
+                    if !cfg!(feature = "defmt") {
+                        let reason = "enum_from(defmt) requires the `defmt` feature of roset";
+                        return Err(Error::new(item.span(), reason));
+                    }
+                    self.defmt = true;
+                }
+                "http" => {
+                    item.flag()?;
+                    if !cfg!(feature = "http") {
+                        let reason = "enum_from(http) requires the `http` feature of roset";
+                        return Err(Error::new(item.span(), reason));
+                    }
+                    self.http = true;
+                }
+                "serde_as_str" => {
+                    item.flag()?;
+                    if !cfg!(feature = "serde") {
+                        let reason =
+                            "enum_from(serde_as_str) requires the `serde` feature of roset";
+                        return Err(Error::new(item.span(), reason));
+                    }
+                    self.serde_as_str = true;
+                }
+                "web" => {
+                    item.flag()?;
+                    if !cfg!(feature = "web") {
+                        let reason = "enum_from(web) requires the `web` feature of roset";
+                        return Err(Error::new(item.span(), reason));
+                    }
+                    self.web = true;
+                }
+                "async_graphql" => {
+                    item.flag()?;
+                    if !cfg!(feature = "async_graphql") {
+                        let reason =
+                            "enum_from(async_graphql) requires the `async_graphql` feature of roset";
+                        return Err(Error::new(item.span(), reason));
+                    }
+                    self.async_graphql = true;
+                }
+                "ts" => {
+                    item.flag()?;
+                    if !cfg!(feature = "ts") {
+                        let reason = "enum_from(ts) requires the `ts` feature of roset";
+                        return Err(Error::new(item.span(), reason));
+                    }
+                    self.ts = true;
+                }
+                key @ ("serde_tag" | "serde_content") => {
+                    if !cfg!(feature = "serde") {
+                        let reason =
+                            format!("enum_from({}) requires the `serde` feature of roset", key);
+                        return Err(Error::new(item.span(), reason));
+                    }
+                    let name = Some(item.str_value()?.value());
+                    match key {
+                        "serde_tag" => self.serde_tag = name,
+                        _ => self.serde_content = name,
+                    }
+                }
+                "prefix" => self.prefix = item.str_value()?.value(),
+                "suffix" => self.suffix = item.str_value()?.value(),
+                "namespace" => self.namespace = Some(item.str_value()?.value()),
+                "with_matcher" => self.matcher = Some(item.str_value()?.parse()?),
+                "strategy" => {
+                    let strategy = item.str_value()?.value();
+                    if !["auto", "match", "table"].contains(&strategy.as_str()) {
+                        let reason = format!(
+                            "unknown strategy {:?}, expected \"auto\", \"match\" or \"table\"",
+                            strategy
+                        );
+                        return Err(Error::new(item.str_value()?.span(), reason));
+                    }
+                    self.strategy = strategy;
+                }
+                "error_msg" => self.error_msg = Some(parse_error_msg(item.str_value()?)?),
+                "error_owns_input" => {
+                    self.error_input = match item.value {
+                        AttrValue::None => ErrorInput::Owned,
+                        _ => ErrorInput::Buffer(item.int_value()?.base10_parse()?),
+                    }
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        let options = RosetOptions::parse(&self.enum_attrs)?;
+        self.sort_str_match = options.sort_str_match;
+        self.gen_tests = options.gen_tests;
+        self.manifest = options.manifest;
+        if options.sealed {
+            let default = format_ident!("{}Roset", self.enum_name);
+            self.sealed_name = Some(options.name("sealed_name", default));
+        }
+        self.error_name = options.name("error_name", self.error_name.clone());
+        self.set_name = options.name("set_name", self.set_name.clone());
+
+        if self.accessors.is_empty() {
+            self.accessors.push(format_ident!("to_str"));
+        }
+        if self.serde_content.is_some() && self.serde_tag.is_none() {
+            let reason = "enum_from(serde_content) requires serde_tag";
+            return Err(Error::new(self.enum_name.span(), reason));
+        }
+        if self.web && self.serde_tag.is_some() {
+            let reason = "enum_from(web) can not be combined with serde_tag";
+            return Err(Error::new(self.enum_name.span(), reason));
+        }
+        let uses_input = self
+            .error_msg
+            .iter()
+            .flatten()
+            .any(|part| matches!(part, MessagePart::Input));
+        if uses_input && matches!(self.error_input, ErrorInput::Discard) {
+            let reason = "error_msg placeholder {input} requires enum_from(error_owns_input)";
+            return Err(Error::new(self.enum_name.span(), reason));
+        }
+        // toml has no null value for variants without str
+        if self.partial && self.value_formats.iter().any(|format| format == "toml") {
+            let reason = "enum_from(toml) can not be combined with partial";
+            return Err(Error::new(self.enum_name.span(), reason));
+        }
+        if self.rename_all.is_none() {
+            let decorated = [
+                ("strip_prefix", self.strip_prefix.is_some()),
+                ("prefix", !self.prefix.is_empty()),
+                ("suffix", !self.suffix.is_empty()),
+            ];
+            if let Some((name, _)) = decorated.iter().find(|(_, used)| *used) {
+                let reason = format!("{} attribute requires rename_all on the enum", name);
+                return Err(Error::new(self.enum_name.span(), reason));
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_variant_attributes(&mut self) -> Result<()> {
+        let variants = self.enum_data.variants.clone();
+        self.variants = variants.iter().map(|var| var.ident.clone()).collect();
+
+        for var in variants.iter() {
+            let var_name = &var.ident;
+            let mut skipped = vec![];
+            for (i, field) in var.fields.iter().enumerate() {
+                for item in parse_items("enum_from", &field.attrs, FIELD_ATTRIBUTES)? {
+                    item.flag()?;
+                    skipped.push(i);
+                }
+            }
+
+            let mut items = parse_items("enum_from", &var.attrs, VARIANT_ATTRIBUTES)?;
+            // a variant has a single str, a repeated attribute is most likely a merge leftover
+            for (i, item) in items
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| !item.is("inner_from"))
+            {
+                if items[..i].iter().any(|earlier| earlier.key == item.key) {
+                    let reason = format!(
+                        "{}::{}: enum_from({}) used twice on the variant",
+                        self.enum_name, var_name, item.key
+                    );
+                    return Err(Error::new(item.span(), reason));
+                }
+            }
+            if self.strum_compat {
+                // explicit enum_from(str) takes precedence
+                let has_str = items.iter().any(|item| item.is("str"));
+                let (strum, aliases) = parse_strum_attributes(var_name, &var.attrs, has_str)?;
+                items.extend(strum);
+                let aliases = aliases.into_iter().map(|alias| (var_name.clone(), alias));
+                self.str_aliases.extend(aliases);
+            }
+
+            for item in items {
+                match item.key.to_string().as_str() {
+                    "str" => {
+                        let value = match item.expr()? {
+                            Expr::Path(path) => {
+                                self.str_consts.push((var_name.clone(), path.clone()));
+                                quote!(#path).to_string().replace(' ', "")
+                            }
+                            _ => item.str_value()?.value(),
+                        };
+                        self.from_str.push((var_name.clone(), value));
+                    }
+                    "inner" => {
+                        if let AttrValue::Expr(_) = item.value {
+                            // `inner = "0"` or `inner = "name"` maps only the selected field
+                            let selected = item.str_value()?;
+                            let position = var.fields.iter().enumerate().position(|(i, field)| {
+                                match &field.ident {
+                                    Some(ident) => ident == &selected.value(),
+                                    None => i.to_string() == selected.value(),
+                                }
+                            });
+                            let position = match position {
+                                Some(position) if skipped.is_empty() => position,
+                                Some(_) => {
+                                    let reason = "inner = \"..\" can not be used with skip_field";
+                                    return Err(Error::new(item.span(), reason));
+                                }
+                                None => {
+                                    let reason =
+                                        format!("no field {:?} on {}", selected.value(), var_name);
+                                    return Err(Error::new(selected.span(), reason));
+                                }
+                            };
+                            skipped = (0..var.fields.len()).filter(|i| *i != position).collect();
+                        }
+                        self.from_inner_enum.push(var_name.clone());
+                    }
+                    "default" => {
+                        item.flag()?;
+                        if let Some(default) = &self.default_variant {
+                            let reason = format!("default attribute already used on {}", default);
+                            return Err(Error::new(item.span(), reason));
+                        }
+                        self.default_variant = Some(var_name.clone());
+                    }
+                    "other" => {
+                        item.flag()?;
+                        if let Some(other) = &self.other_variant {
+                            let reason = format!("other attribute already used on {}", other);
+                            return Err(Error::new(item.span(), reason));
+                        }
+                        self.other_variant = Some(var_name.clone());
+                    }
+                    "flatten" => {
+                        item.flag()?;
+                        let inner_type = match &var.fields {
+                            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                                fields.unnamed[0].ty.clone()
+                            }
+                            _ => {
+                                let reason =
+                                    "flatten attribute requires a variant like Inner(InnerEnum)";
+                                return Err(Error::new(item.span(), reason));
+                            }
+                        };
+                        self.flatten.push((var_name.clone(), inner_type));
+                    }
+                    "hidden" => item.flag().map(|_| self.hidden.push(var_name.clone()))?,
+                    "inner_from" => {
+                        let source = item.str_value()?.parse()?;
+                        self.inner_from.push((var_name.clone(), source));
+                    }
+                    _ => unreachable!(),
+                }
+            }
+
+            if !skipped.is_empty() {
+                let reason = match self.from_inner_enum.contains(var_name) {
+                    false => Some("skip_field attribute requires inner on the variant"),
+                    true if skipped.len() == var.fields.len() => {
+                        Some("inner attribute requires a field without skip_field")
+                    }
+                    true => None,
+                };
+                if let Some(reason) = reason {
+                    return Err(Error::new(var_name.span(), reason));
+                }
+                self.skip_fields.push((var_name.clone(), skipped));
+            }
+
+            // inner_from widens into the one field, or the one selected by inner
+            let widened = self.inner_from.iter().any(|(var, _)| var.eq(var_name));
+            let kept = var.fields.len()
+                - self
+                    .skip_fields
+                    .iter()
+                    .find(|(var, _)| var.eq(var_name))
+                    .map_or(0, |(_, skipped)| skipped.len());
+            if widened && kept != 1 {
+                let reason = "inner_from attribute requires a variant with one field, \
+                              or inner selecting one";
+                return Err(Error::new(var_name.span(), reason));
+            }
+
+            let flattened = self.flatten.iter().any(|(var, _)| var.eq(var_name));
+            if flattened && self.has_str(var_name) {
+                let reason = "flatten attribute can not be used with str";
+                return Err(Error::new(var_name.span(), reason));
+            }
+
+            // explicit str, then strum, then the renamed variant name, see the EnumFrom docs
+            let catch_all = flattened || self.other_variant.as_ref() == Some(var_name);
+            if let (Some(case), false) = (&self.rename_all, catch_all || self.has_str(var_name)) {
+                let name = var_name.to_string();
+                let name = match &self.strip_prefix {
+                    Some(prefix) => name.strip_prefix(prefix.as_str()).unwrap_or(&name),
+                    None => &name,
+                };
+                let value = crate::rename_case(name, case).unwrap();
+                let value = format!("{}{}{}", self.prefix, value, self.suffix);
+                self.from_str.push((var_name.clone(), value));
+            }
+        }
+        self.check_str_consts()?;
+        // the namespace covers explicit str too, so flattening enums keeps strings apart
+        if let Some(namespace) = &self.namespace {
+            for (_, value) in self.from_str.iter_mut().chain(self.str_aliases.iter_mut()) {
+                *value = format!("{}.{}", namespace, value);
+            }
+        }
+        self.check_str_values()
+    }
+
+    /// Options reading the str values in the macro can not be used with `str = CONST`
+    fn check_str_consts(&self) -> Result<()> {
+        let (var_name, _) = match self.str_consts.first() {
+            Some(str_const) => str_const,
+            None => return Ok(()),
+        };
+        let uses_expected = self
+            .error_msg
+            .iter()
+            .flatten()
+            .any(|part| matches!(part, MessagePart::Expected));
+        let conflicts = [
+            ("namespace", self.namespace.is_some()),
+            ("sort_str_match", self.sort_str_match),
+            ("strategy = \"table\"", self.strategy == "table"),
+            ("http", self.http),
+            ("serde_tag", self.serde_tag.is_some()),
+            ("error_msg placeholder {expected}", uses_expected),
+            ("roset(manifest)", self.manifest),
+            ("ts", self.ts),
+            ("async_graphql", self.async_graphql),
+        ];
+        if let Some((conflict, _)) = conflicts.iter().find(|(_, enabled)| *enabled) {
+            let reason = format!(
+                "{}::{}: str = CONST can not be combined with {}",
+                self.enum_name, var_name, conflict
+            );
+            return Err(Error::new(var_name.span(), reason));
+        }
+        Ok(())
+    }
+
+    fn is_str_const(&self, variant: &Ident) -> bool {
+        self.str_consts.iter().any(|(var, _)| var.eq(variant))
+    }
+
+    /// The str of the variant, a literal or the const path of `str = CONST`
+    fn str_tokens(&self, variant: &Ident, value: &str) -> TokenStream {
+        match self.str_consts.iter().find(|(var, _)| var.eq(variant)) {
+            Some((_, path)) => quote! { #path },
+            None => quote! { #value },
+        }
+    }
+
+    /// Reject empty str and str used by two variants, which may only collide after rename_all,
+    /// values of consts are checked by a const assertion instead
+    fn check_str_values(&self) -> Result<()> {
+        for (i, (var_name, value)) in self.from_str.iter().enumerate() {
+            let title = format!("{}::{}", self.enum_name, var_name);
+            if self.is_str_const(var_name) {
+                continue;
+            }
+            if value.is_empty() {
+                let reason = format!("{}: str value can not be empty", title);
+                return Err(Error::new(var_name.span(), reason));
+            }
+            let used = self.from_str[..i]
+                .iter()
+                .find(|(other, v)| v.eq(value) && !self.is_str_const(other));
+            if let Some((other, _)) = used {
+                let reason = format!("{}: str {:?} already used by {}", title, value, other);
+                return Err(Error::new(var_name.span(), reason));
+            }
+        }
+        for (i, (var_name, value)) in self.str_aliases.iter().enumerate() {
+            let used = self
+                .from_str
+                .iter()
+                .filter(|(other, _)| !self.is_str_const(other))
+                .chain(self.str_aliases[..i].iter())
+                .find(|(_, v)| v.eq(value));
+            if let Some((other, _)) = used {
+                let reason = format!(
+                    "{}::{}: strum serialize {:?} already used by {}",
+                    self.enum_name, var_name, value, other
+                );
+                return Err(Error::new(var_name.span(), reason));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn write_output(&self) -> TokenStream {
+        let mut output = TokenStream::new();
+        // impls of roset-core traits, gathered so sealed enums can move them to their own trait
+        let mut traits = TokenStream::new();
+        let vars_from_str =
+            self.from_str.len() + self.other_variant.iter().count() + self.flatten.len();
+        if vars_from_str > 0 {
+            output.extend(self.write_parse_error());
+            output.extend(self.write_from_str());
+            if !self.str_consts.is_empty() {
+                let title = format!("EnumFrom for {}", self.enum_name);
+                let values: Vec<_> = self
+                    .from_str
+                    .iter()
+                    .map(|(variant, value)| (variant, self.str_tokens(variant, value)))
+                    .collect();
+                let values: Vec<_> = values
+                    .iter()
+                    .map(|(variant, value)| (*variant, value))
+                    .collect();
+                output.extend(crate::const_assert_unique(
+                    &title,
+                    &quote! { &str },
+                    &values,
+                    true,
+                ));
+            }
+            output.extend(self.write_parse_prefix());
+            if self.from_os_str {
+                output.extend(self.write_from_os_str());
+            }
+            if self.from_bytes {
+                output.extend(self.write_from_bytes());
+            }
+            if !self.partial {
+                traits.extend(self.write_str_repr());
+            }
+            if self.flags {
+                output.extend(self.write_flags());
+            }
+            if self.gen_tests.is_some() {
+                output.extend(self.write_tests());
+            }
+            for format in self.value_formats.iter() {
+                output.extend(self.write_value_format(format));
+            }
+            for string_type in self.string_types.iter() {
+                output.extend(self.write_string_type(string_type));
+            }
+            if self.http {
+                output.extend(self.write_http());
+            }
+            if self.defmt {
+                output.extend(self.write_defmt());
+            }
+            if self.str_ids {
+                output.extend(self.write_str_ids());
+            }
+            if self.serde_as_str {
+                output.extend(self.write_serde_as_str());
+            }
+            if self.eq_str {
+                output.extend(self.write_eq_str());
+            }
+            if self.serde_tag.is_some() {
+                output.extend(self.write_serde_tagged());
+            }
+            if self.web {
+                output.extend(self.write_web());
+            }
+            if self.ts {
+                output.extend(self.write_ts());
+            }
+            if self.async_graphql {
+                output.extend(self.write_async_graphql());
+            }
+        }
+
+        output.extend(self.write_from_inner());
+        if self.manifest {
+            output.extend(self.write_manifest());
+        }
+        traits.extend(self.write_core_traits());
+        match &self.sealed_name {
+            Some(sealed_name) => output.extend(self.write_sealed(sealed_name, traits)),
+            None => output.extend(traits),
+        }
+        output
+    }
+
+    /// Path of the trait with `variant_name`, seen from the generated test module
+    fn variant_name_trait(&self) -> TokenStream {
+        match &self.sealed_name {
+            Some(sealed_name) => quote! { super::#sealed_name },
+            None => quote! { ::roset::VariantName },
+        }
+    }
+
+    /// Merges the impls of roset-core traits into one trait only this enum can implement
+    fn write_sealed(&self, sealed_name: &Ident, traits: TokenStream) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let module = format_ident!(
+            "{}_roset_sealed",
+            crate::to_snake_case(&enum_name.to_string())
+        );
+        let doc = format!(
+            "Generated functionality of `{}`, sealed so it cannot be implemented outside this crate",
+            enum_name
+        );
+
+        let file: syn::File = syn::parse2(traits).unwrap();
+        let mut output = TokenStream::new();
+        let mut trait_items = vec![];
+        let mut impl_items = vec![];
+        for item in file.items {
+            match item {
+                syn::Item::Impl(item) if item.trait_.is_some() => {
+                    for impl_item in item.items {
+                        match &impl_item {
+                            syn::ImplItem::Method(method) => {
+                                let sig = &method.sig;
+                                trait_items.push(quote! { #sig; });
+                            }
+                            syn::ImplItem::Const(item) => {
+                                let (name, ty) = (&item.ident, &item.ty);
+                                trait_items.push(quote! { const #name: #ty; });
+                            }
+                            _ => unreachable!(),
+                        }
+                        impl_items.push(impl_item);
+                    }
+                }
+                item => output.extend(quote! { #item }),
+            }
+        }
+
+        output.extend(quote! {
+            #[allow(non_snake_case)]
+            mod #module {
+                pub trait Sealed {}
+            }
+
+            impl #module::Sealed for #enum_name {}
+
+            #[doc = #doc]
+            #vis trait #sealed_name: #module::Sealed + ::core::marker::Sized {
+                #(#trait_items)*
+            }
+
+            #[allow(deprecated)]
+            impl #sealed_name for #enum_name {
+                #(#impl_items)*
+            }
+        });
+        output
+    }
+
+    fn write_core_traits(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let count = self.variants.len();
+        let names = self.variants.iter().map(|var| {
+            let name = var.to_string();
+            quote! { Self::#var { .. } => #name }
+        });
+        let metadata = self
+            .enum_data
+            .variants
+            .iter()
+            .enumerate()
+            .filter(|(_, var)| !self.is_hidden(&var.ident))
+            .map(|(ordinal, var)| {
+                let name = var.ident.to_string();
+                let str_value = match self.from_str.iter().find(|(other, _)| other.eq(&var.ident)) {
+                    Some((var, value)) => {
+                        let value = self.str_tokens(var, value);
+                        quote! { Some(#value) }
+                    }
+                    None => quote! { None },
+                };
+                let has_payload = !var.fields.is_empty();
+                quote! {
+                    ::roset::VariantMeta {
+                        name: #name,
+                        str_value: #str_value,
+                        ordinal: #ordinal,
+                        has_payload: #has_payload,
+                    }
+                }
+            });
+
+        quote! {
+            #[allow(deprecated)]
+            impl ::roset::VariantName for #enum_name {
+                fn variant_name(&self) -> &'static str {
+                    match self {
+                        #(#names,)*
+                    }
+                }
+            }
+
+            impl ::roset::EnumCount for #enum_name {
+                const COUNT: usize = #count;
+            }
+
+            #[allow(deprecated)]
+            impl #enum_name {
+                /// Metadata of every variant but hidden ones in declaration order
+                #vis const METADATA: &'static [::roset::VariantMeta] = &[#(#metadata),*];
+            }
+        }
+    }
+
+    /// `ROSET_MANIFEST` with the name, ordinal, str, discriminant and `enum_value` of every
+    /// variant, `null` when the macro can not know it
+    fn write_manifest(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        // discriminants are only known when all of them are literals
+        let mut next = Some(0);
+        let variants: Vec<_> = self
+            .enum_data
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(ordinal, var)| {
+                if let Some((_, expr)) = &var.discriminant {
+                    next = crate::eval_discriminant(expr);
+                }
+                let discriminant = match next {
+                    Some(value)
+                        if self
+                            .enum_data
+                            .variants
+                            .iter()
+                            .all(|var| var.fields.is_empty()) =>
+                    {
+                        value.to_string()
+                    }
+                    _ => "null".to_string(),
+                };
+                next = next.map(|value| value + 1);
+
+                let str_value = match self.from_str.iter().find(|(other, _)| other.eq(&var.ident)) {
+                    Some((_, value)) => json_str(value),
+                    None => "null".to_string(),
+                };
+                let value = parse_items("enum_value", &var.attrs, &crate::options::REPR_TYPES)
+                    .ok()
+                    .and_then(|mut items| items.pop())
+                    .and_then(|item| {
+                        let value = crate::eval_discriminant(item.expr().ok()?)?;
+                        Some(format!(
+                            "{{\"type\":{},\"value\":{}}}",
+                            json_str(&item.key.to_string()),
+                            value
+                        ))
+                    })
+                    .unwrap_or_else(|| "null".to_string());
+                format!(
+                    "{{\"name\":{},\"ordinal\":{},\"str\":{},\"discriminant\":{},\
+                     \"value\":{},\"hidden\":{},\"payload\":{}}}",
+                    json_str(&var.ident.to_string()),
+                    ordinal,
+                    str_value,
+                    discriminant,
+                    value,
+                    self.is_hidden(&var.ident),
+                    !var.fields.is_empty()
+                )
+            })
+            .collect();
+        let manifest = format!(
+            "{{\"name\":{},\"variants\":[{}]}}",
+            json_str(&enum_name.to_string()),
+            variants.join(",")
+        );
+
+        quote! {
+            impl #enum_name {
+                /// JSON description of the variants, generated by `roset(manifest)`
+                #vis const ROSET_MANIFEST: &'static str = #manifest;
+            }
+        }
+    }
+
+    /// `#[cfg(test)]` module checking the str mapping of every variant
+    fn write_tests(&self) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let accessor = &self.accessors[0];
+        let module = format_ident!(
+            "{}_roset_tests",
+            crate::to_snake_case(&enum_name.to_string())
+        );
+        let names = self.from_str.iter().map(|(var, _)| var.to_string());
+        // paths of str consts are resolved from the parent module
+        let values: Vec<_> = self
+            .from_str
+            .iter()
+            .map(
+                |(var, value)| match self.str_consts.iter().find(|(other, _)| other.eq(var)) {
+                    Some((_, path)) if path.path.leading_colon.is_none() => {
+                        let mut segments = path.path.segments.iter().peekable();
+                        match segments.peek().map(|segment| segment.ident.to_string()) {
+                            Some(first) if first == "crate" || first == "super" => quote! { #path },
+                            Some(first) if first == "self" => {
+                                let rest = segments.skip(1);
+                                quote! { super #(::#rest)* }
+                            }
+                            _ => quote! { super::#path },
+                        }
+                    }
+                    _ => self.str_tokens(var, value),
+                },
+            )
+            .collect();
+
+        let variant_name = self.variant_name_trait();
+        let expected = match self.partial {
+            true => quote! { Some(*value) },
+            false => quote! { *value },
+        };
+        // input of every generated `TryFrom`, built from `value: &&'static str`
+        let mut inputs = vec![];
+        if self.from_bytes {
+            inputs.push(quote! { value.as_bytes() });
+        }
+        if self.from_os_str {
+            inputs.push(quote! { ::std::ffi::OsStr::new(value) });
+        }
+        if self.http {
+            inputs.push(quote! { &::http::HeaderValue::from_static(*value) });
+        }
+        for string_type in self.string_types.iter() {
+            let string = string_type_path(string_type);
+            inputs.push(quote! { &#string::new(value) });
+            inputs.push(quote! { #string::new(value) });
+        }
+        for format in self.value_formats.iter() {
+            let (_, string, _, _) = value_format_paths(format);
+            inputs.push(quote! { &#string(value.to_string()) });
+            inputs.push(quote! { #string(value.to_string()) });
+        }
+        let try_from_symmetry = (!inputs.is_empty()).then(|| {
+            quote! {
+                #[test]
+                fn try_from_symmetry() {
+                    for (name, value) in STRS {
+                        #(
+                            let parsed = #enum_name::try_from(#inputs).unwrap();
+                            assert_eq!(#variant_name::variant_name(&parsed), *name);
+                        )*
+                    }
+                }
+            }
+        });
+
+        // a module can not see items of a function body, the error then points at gen_tests
+        let span = self.gen_tests.unwrap_or_else(Span::call_site);
+        let import = quote_spanned!(span=> use super::#enum_name;);
+
+        quote! {
+            #[cfg(test)]
+            #[allow(deprecated, non_snake_case)]
+            mod #module {
+                #import
+
+                const STRS: &[(&str, &str)] = &[#((#names, #values)),*];
+
+                #[test]
+                fn str_round_trip() {
+                    for (name, value) in STRS {
+                        let parsed: #enum_name = value.parse().unwrap();
+                        assert_eq!(#variant_name::variant_name(&parsed), *name);
+                        assert_eq!(parsed.#accessor(), #expected);
+                    }
+                }
+
+                #[test]
+                fn str_unique() {
+                    for (i, (name, value)) in STRS.iter().enumerate() {
+                        if let Some((other, _)) = STRS[..i].iter().find(|(_, v)| v == value) {
+                            panic!("str {:?} of {} already used by {}", value, name, other);
+                        }
+                    }
+                }
+
+                #try_from_symmetry
+            }
+        }
+    }
+
+    fn write_str_repr(&self) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let accessor = &self.accessors[0];
+        // strings of catch-all and flattened variants borrow from the enum
+        let static_str = (self.other_variant.is_none() && self.flatten.is_empty()).then(|| {
+            quote! {
+                impl ::roset::StaticStr for #enum_name {
+                    fn static_str(this: &Self) -> &'static str {
+                        this.#accessor()
+                    }
+                }
+            }
+        });
+
+        quote! {
+            impl ::roset::StrRepr for #enum_name {
+                fn as_str(&self) -> &str {
+                    self.#accessor()
+                }
+
+                fn from_str_repr(s: &str) -> Option<Self> {
+                    ::core::str::FromStr::from_str(s).ok()
+                }
+            }
+
+            #static_str
+        }
+    }
+
+    /// `{Enum}Set` bitset of unit variants, parsed from and formatted to `A|B` strings
+    fn write_flags(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let error_name = self.parse_error_name();
+        let set_name = &self.set_name;
+        let accessor = &self.accessors[0];
+        let doc = format!("Set of `{}` flags, written as `A|B` in strings", enum_name);
+
+        let bits = self.variants.iter().enumerate().map(|(i, var)| {
+            quote! { #enum_name::#var => 1 << #i }
+        });
+        let variants = &self.variants;
+        let visible = variants.iter().filter(|var| !self.is_hidden(var));
+
+        quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+            #vis struct #set_name {
+                bits: u128,
+            }
+
+            #[allow(deprecated)]
+            impl #set_name {
+                const VARIANTS: &'static [#enum_name] = &[#(#enum_name::#variants),*];
+
+                fn bit(flag: &#enum_name) -> u128 {
+                    match flag {
+                        #(#bits,)*
+                    }
+                }
+
+                /// Create an empty set
+                #vis const fn empty() -> Self {
+                    #set_name { bits: 0 }
+                }
+
+                /// Create a set with all flags but hidden ones
+                #vis fn all() -> Self {
+                    [#(#enum_name::#visible),*].iter().collect()
+                }
+
+                /// Returns the raw bits, flag bit index is the variant ordinal
+                #vis const fn bits(&self) -> u128 {
+                    self.bits
+                }
+
+                /// Returns `true` if the set contains the flag
+                #vis fn contains(&self, flag: &#enum_name) -> bool {
+                    self.bits & Self::bit(flag) != 0
+                }
+
+                /// Add a flag, returns `true` if it was not present
+                #vis fn insert(&mut self, flag: &#enum_name) -> bool {
+                    let absent = !self.contains(flag);
+                    self.bits |= Self::bit(flag);
+                    absent
+                }
+
+                /// Remove a flag, returns `true` if it was present
+                #vis fn remove(&mut self, flag: &#enum_name) -> bool {
+                    let present = self.contains(flag);
+                    self.bits &= !Self::bit(flag);
+                    present
+                }
+
+                /// Returns `true` if the set contains no flag
+                #vis const fn is_empty(&self) -> bool {
+                    self.bits == 0
+                }
+
+                /// Returns the number of flags in the set
+                #vis const fn len(&self) -> usize {
+                    self.bits.count_ones() as usize
+                }
+
+                /// Iterate over flags in variant order
+                #vis fn iter(&self) -> impl Iterator<Item = &'static #enum_name> + '_ {
+                    Self::VARIANTS.iter().filter(move |flag| self.contains(flag))
+                }
+            }
+
+            impl<'a> ::core::iter::FromIterator<&'a #enum_name> for #set_name {
+                fn from_iter<I: IntoIterator<Item = &'a #enum_name>>(iter: I) -> Self {
+                    let mut set = Self::empty();
+                    for flag in iter {
+                        set.insert(flag);
+                    }
+                    set
+                }
+            }
+
+            impl ::core::ops::BitOr for #set_name {
+                type Output = Self;
+
+                fn bitor(self, rhs: Self) -> Self {
+                    #set_name { bits: self.bits | rhs.bits }
+                }
+            }
+
+            impl From<#enum_name> for #set_name {
+                fn from(flag: #enum_name) -> Self {
+                    #set_name { bits: Self::bit(&flag) }
+                }
+            }
+
+            impl ::std::str::FromStr for #set_name {
+                type Err = #error_name;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    let mut set = Self::empty();
+                    for flag in s.split('|').map(str::trim).filter(|flag| !flag.is_empty()) {
+                        let flag: #enum_name = flag.parse()?;
+                        set.insert(&flag);
+                    }
+                    Ok(set)
+                }
+            }
+
+            impl ::core::fmt::Display for #set_name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    for (i, flag) in self.iter().enumerate() {
+                        if i > 0 {
+                            f.write_str("|")?;
+                        }
+                        f.write_str(flag.#accessor())?;
+                    }
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// Construct the variant parsed from str, payload fields take `Default::default()`
+    fn write_construct(&self, variant: &Ident) -> TokenStream {
+        let var = self
+            .enum_data
+            .variants
+            .iter()
+            .find(|var| var.ident.eq(variant))
+            .unwrap();
+
+        let default = quote! { ::core::default::Default::default() };
+        match &var.fields {
+            Fields::Unit => quote! { Self::#variant },
+            Fields::Unnamed(fields) => {
+                let values = fields.unnamed.iter().map(|_| &default);
+                quote! { Self::#variant(#(#values),*) }
+            }
+            Fields::Named(fields) => {
+                let names = fields.named.iter().map(|field| &field.ident);
+                quote! { Self::#variant { #(#names: #default),* } }
+            }
+        }
+    }
+
+    fn has_str(&self, variant: &Ident) -> bool {
+        self.from_str.iter().any(|(var, _)| var.eq(variant))
+    }
+
+    /// Str mapping in the order of parsing match arms
+    fn str_arms(&self) -> Vec<&(Ident, String)> {
+        let mut arms: Vec<_> = self.from_str.iter().collect();
+        if self.sort_str_match {
+            arms.sort_by(|(_, a), (_, b)| (a.len(), a.as_bytes()).cmp(&(b.len(), b.as_bytes())));
+        }
+        arms
+    }
+
+    /// Whether the enum is fieldless with a static str on every variant, exposed as `VARIANT_STRS`
+    fn has_str_array(&self) -> bool {
+        let fieldless = self
+            .enum_data
+            .variants
+            .iter()
+            .all(|var| matches!(var.fields, Fields::Unit));
+        fieldless && !self.partial && self.from_str.len() == self.variants.len()
+    }
+
+    fn is_hidden(&self, variant: &Ident) -> bool {
+        self.hidden.contains(variant)
+    }
+
+    /// Whether parsing could fail for input not found in the str mapping
+    fn fallible(&self) -> bool {
+        self.default_variant.is_none() && self.other_variant.is_none()
+    }
+
+    fn parse_error_name(&self) -> Ident {
+        self.error_name.clone()
+    }
+
+    fn write_parse_error(&self) -> TokenStream {
+        let vis = &self.vis;
+        let error_name = self.parse_error_name();
+        let doc = format!("Error returned when parsing `{}` fails", self.enum_name);
+        let not_found = format!("input does not match any variant of {}", self.enum_name);
+        let not_found_input = format!(
+            "input {{:?}} does not match any variant of {}",
+            self.enum_name
+        );
+
+        let (derive_copy, not_found_variant, not_found_new, not_found_input_fn) = match self
+            .error_input
+        {
+            ErrorInput::Discard => (
+                quote! { Copy, },
+                quote! { NotFound },
+                quote! { #error_name::NotFound },
+                quote! { None },
+            ),
+            ErrorInput::Owned => (
+                quote! {},
+                quote! { NotFound(::std::string::String) },
+                quote! {
+                    #error_name::NotFound(::std::string::String::from_utf8_lossy(input).into_owned())
+                },
+                quote! {
+                    match self {
+                        #error_name::NotFound(input) => Some(input.as_str()),
+                        _ => None,
+                    }
+                },
+            ),
+            ErrorInput::Buffer(size) => (
+                quote! { Copy, },
+                quote! { NotFound([u8; #size], usize) },
+                quote! {{
+                    let mut buffer = [0; #size];
+                    let len = input.len().min(#size);
+                    buffer[..len].copy_from_slice(&input[..len]);
+                    #error_name::NotFound(buffer, len)
+                }},
+                quote! {
+                    match self {
+                        #error_name::NotFound(buffer, len) => {
+                            // input may be truncated in the middle of a character
+                            let input = &buffer[..*len];
+                            let valid = match ::core::str::from_utf8(input) {
+                                Ok(_) => input,
+                                Err(err) => &input[..err.valid_up_to()],
+                            };
+                            ::core::str::from_utf8(valid).ok()
+                        }
+                        _ => None,
+                    }
+                },
+            ),
+        };
+
+        let (not_found_pattern, not_found_display) = match self.error_input {
+            _ if self.error_msg.is_some() => (
+                match self.error_input {
+                    ErrorInput::Discard => quote! { #error_name::NotFound },
+                    _ => quote! { #error_name::NotFound(..) },
+                },
+                self.write_error_msg(),
+            ),
+            ErrorInput::Discard => (
+                quote! { #error_name::NotFound },
+                quote! { f.write_str(#not_found) },
+            ),
+            _ => (
+                quote! { #error_name::NotFound(..) },
+                quote! { ::core::write!(f, #not_found_input, self.input().unwrap_or_default()) },
+            ),
+        };
+
+        // only conversions from format values reject input by its type
+        let (unexpected_type_variant, unexpected_type_display) = match self.value_formats.is_empty()
+        {
+            true => (quote! {}, quote! {}),
+            false => (
+                quote! {
+                    /// The input value is not a string, or a number accepted by `accept_ordinal`
+                    UnexpectedType,
+                },
+                quote! { #error_name::UnexpectedType => f.write_str("input value is not a string"), },
+            ),
+        };
+
+        quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, #derive_copy PartialEq, Eq)]
+            #vis enum #error_name {
+                /// The input does not match any variant
+                #not_found_variant,
+                /// The input is not valid UTF-8
+                InvalidUtf8,
+                #unexpected_type_variant
+            }
+
+            impl #error_name {
+                #[allow(dead_code)]
+                fn not_found(input: &[u8]) -> Self {
+                    let _ = input;
+                    #not_found_new
+                }
+
+                /// The rejected input, if kept by the error
+                #vis fn input(&self) -> Option<&str> {
+                    #not_found_input_fn
+                }
+            }
+
+            impl ::core::fmt::Display for #error_name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match self {
+                        #not_found_pattern => #not_found_display,
+                        #error_name::InvalidUtf8 => f.write_str("input is not valid UTF-8"),
+                        #unexpected_type_display
+                    }
+                }
+            }
+
+            impl ::core::error::Error for #error_name {}
+
+            impl From<::core::convert::Infallible> for #error_name {
+                fn from(err: ::core::convert::Infallible) -> Self {
+                    match err {}
+                }
+            }
+        }
+    }
+
+    /// Display of `error_msg`, with `{expected}` listing the str values and `{input}` read back
+    fn write_error_msg(&self) -> TokenStream {
+        let escape = |text: &str| text.replace('{', "{{").replace('}', "}}");
+        let expected: Vec<_> = self
+            .from_str
+            .iter()
+            .filter(|(variant, _)| !self.is_hidden(variant))
+            .map(|(_, value)| value.as_str())
+            .collect();
+        let mut uses_input = false;
+        let format: String = self
+            .error_msg
+            .iter()
+            .flatten()
+            .map(|part| match part {
+                MessagePart::Text(text) => escape(text),
+                MessagePart::Expected => escape(&expected.join(", ")),
+                MessagePart::Input => {
+                    uses_input = true;
+                    "{input}".to_string()
+                }
+            })
+            .collect();
+        match uses_input {
+            true => quote! { ::core::write!(f, #format, input = self.input().unwrap_or_default()) },
+            false => quote! { ::core::write!(f, #format) },
+        }
+    }
+
+    /// Match arm for input not found in the str mapping, `bytes` for input of `&[u8]`
+    fn write_fallback_arm(&self, bytes: bool) -> TokenStream {
+        let error_name = self.parse_error_name();
+        match (&self.default_variant, &self.other_variant) {
+            (Some(default), _) => quote! { _ => Ok(Self::#default) },
+            (_, Some(other)) if bytes => quote! {
+                s => ::core::str::from_utf8(s)
+                    .map(|s| Self::#other(::core::convert::From::from(s)))
+                    .map_err(|_| #error_name::InvalidUtf8)
+            },
+            (_, Some(other)) => quote! { s => Ok(Self::#other(::core::convert::From::from(s))) },
+            (None, None) if bytes => quote! { s => Err(#error_name::not_found(s)) },
+            (None, None) => quote! { s => Err(#error_name::not_found(s.as_bytes())) },
+        }
+    }
+
+    /// Fallback match arm trying flattened inner enums, then the `with_matcher` function, before
+    /// `write_fallback_arm`
+    fn write_flatten_arm(&self, bytes: bool) -> TokenStream {
+        let fallback = self.write_fallback_arm(bytes);
+        if self.flatten.is_empty() && self.matcher.is_none() {
+            return fallback;
+        }
+
+        let tries = self.flatten.iter().map(|(variant, inner_type)| {
+            quote! {
+                if let Some(inner) = <#inner_type as ::roset::StrRepr>::from_str_repr(text) {
+                    return Ok(Self::#variant(inner));
+                }
+            }
+        });
+        let matcher = self.matcher.iter().map(|matcher| {
+            quote! {
+                if let Some(value) = #matcher(text) {
+                    return Ok(value);
+                }
+            }
+        });
+        let text = match bytes {
+            true => quote! { if let Ok(text) = ::core::str::from_utf8(s) },
+            false => quote! { let text = s; },
+        };
+
+        quote! {
+            s => {
+                #text {
+                    #(#tries)*
+                    #(#matcher)*
+                }
+                match s {
+                    #fallback,
+                }
+            }
+        }
+    }
+
+    /// `parse_prefix` recognizing the longest str at the start of the input
+    fn write_parse_prefix(&self) -> TokenStream {
+        if self.from_str.is_empty() {
+            return TokenStream::new();
+        }
+
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let mut arms: Vec<_> = self.from_str.iter().collect();
+        // stable sort keeps declaration order among strings of the same length
+        arms.sort_by_key(|(_, value)| std::cmp::Reverse(value.len()));
+        let arms: Vec<_> = arms
+            .into_iter()
+            .map(|(variant, value)| {
+                let variant = self.write_construct(variant);
+                quote! {
+                    if let Some(rest) = s.strip_prefix(#value) {
+                        return Some((#variant, rest));
+                    }
+                }
+            })
+            .collect();
+        // lengths of str consts are only known at run time
+        let body = match self.str_consts.is_empty() {
+            true => quote! {
+                #(#arms)*
+                None
+            },
+            false => {
+                let values = self
+                    .from_str
+                    .iter()
+                    .map(|(variant, value)| self.str_tokens(variant, value));
+                let constructs = self.from_str.iter().enumerate().map(|(i, (variant, _))| {
+                    let variant = self.write_construct(variant);
+                    quote! { #i => #variant }
+                });
+                quote! {
+                    let mut longest: Option<(usize, usize)> = None;
+                    for (i, value) in [#(#values),*].iter().enumerate() {
+                        if s.starts_with(value) && longest.map_or(true, |(len, _)| value.len() > len) {
+                            longest = Some((value.len(), i));
+                        }
+                    }
+                    let (len, index) = longest?;
+                    let variant = match index {
+                        #(#constructs,)*
+                        _ => ::core::unreachable!(),
+                    };
+                    Some((variant, &s[len..]))
+                }
+            }
+        };
+
+        // a shorter str matching the start of a longer one only loses to it in parse_prefix
+        let pairs: Vec<_> = self
+            .from_str
+            .iter()
+            .filter(|(short, _)| !self.is_str_const(short))
+            .flat_map(|(short, prefix)| {
+                self.from_str
+                    .iter()
+                    .filter(|(long, _)| !self.is_str_const(long))
+                    .filter(move |(_, value)| {
+                        value.len() > prefix.len() && value.starts_with(prefix.as_str())
+                    })
+                    .map(move |(long, value)| {
+                        format!("{:?} of {} and {:?} of {}", prefix, short, value, long)
+                    })
+            })
+            .collect();
+        let overlap = (!pairs.is_empty() && !self.allow_prefix_overlap).then(|| {
+            let note = format!(
+                "{}: str is a prefix of another, parse_prefix takes the longest: {}; \
+                 silence with enum_from(allow_prefix_overlap)",
+                enum_name,
+                pairs.join(", ")
+            );
+            crate::warning(enum_name.span(), "STR_PREFIX_OVERLAP", &note)
+        });
+
+        quote! {
+            #overlap
+
+            #[allow(deprecated)]
+            impl #enum_name {
+                /// Returns the variant whose str is the longest prefix of the input, with the rest
+                /// of the input, ignoring catch-all and flattened variants
+                #vis fn parse_prefix(s: &str) -> Option<(Self, &str)> {
+                    #body
+                }
+            }
+        }
+    }
+
+    /// Binary search of the input in the sorted str values before falling back to matching,
+    /// `bytes` for input of `&[u8]`
+    fn write_table_lookup(&self, bytes: bool) -> TokenStream {
+        let mut sorted: Vec<_> = self.from_str.iter().collect();
+        sorted.sort_by(|(_, a), (_, b)| a.cmp(b));
+        let count = sorted.len();
+        let (key_type, keys): (_, Vec<_>) = match bytes {
+            true => (
+                quote! { &[u8] },
+                sorted
+                    .iter()
+                    .map(|(_, value)| {
+                        let value = LitByteStr::new(value.as_bytes(), Span::call_site());
+                        quote! { #value }
+                    })
+                    .collect(),
+            ),
+            false => (
+                quote! { &str },
+                sorted.iter().map(|(_, value)| quote! { #value }).collect(),
+            ),
+        };
+        let arms = sorted.iter().enumerate().map(|(i, (variant, _))| {
+            let variant = self.write_construct(variant);
+            quote! { #i => #variant }
+        });
+
+        quote! {
+            const TABLE: [#key_type; #count] = [#(#keys),*];
+            if let Ok(index) = TABLE.binary_search(&s) {
+                return Ok(match index {
+                    #(#arms,)*
+                    _ => ::core::unreachable!(),
+                });
+            }
+        }
+    }
+
+    fn write_from_str(&self) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let error_name = self.parse_error_name();
+        let from_str = self.str_arms().into_iter().map(|(variant, value)| {
+            let value = self.str_tokens(variant, value);
+            let variant = self.write_construct(variant);
+            quote! { #value => Ok(#variant) }
+        });
+        // with str ids the strings are matched once, by `str_id_of`
+        let (from_str, from_str_id) = match self.str_ids {
+            _ if self.table => (vec![], Some(self.write_table_lookup(false))),
+            true => (
+                vec![],
+                Some(quote! {
+                    if let Some(value) = Self::str_id_of(s).and_then(Self::from_str_id) {
+                        return Ok(value);
+                    }
+                }),
+            ),
+            false => (from_str.collect(), None),
+        };
+
+        let discriminants = if self.accept_ordinal {
+            crate::get_discriminants(enum_name, &self.enum_data)
+        } else {
+            vec![]
+        };
+
+        let from_ordinal = discriminants.into_iter().map(|(variant, value)| {
+            let value = value.to_string();
+            let variant = self.write_construct(&variant);
+            quote! { #value => Ok(#variant) }
+        });
+        let from_alias = self.str_aliases.iter().map(|(variant, value)| {
+            let variant = self.write_construct(variant);
+            quote! { #value => Ok(#variant) }
+        });
+
+        // variants without str get None in partial mode
+        let wrap = |value: TokenStream| match self.partial {
+            true => quote! { Some(#value) },
+            false => value,
+        };
+
+        let to_str = self.from_str.iter().map(|(variant, value)| {
+            let value = wrap(self.str_tokens(variant, value));
+            quote! { Self::#variant { .. } => #value }
+        });
+
+        // str of catch-all and flattened variants borrows from the enum
+        let (to_str_other, mut str_type) = match &self.other_variant {
+            Some(other) => {
+                let value = wrap(quote! { ::core::convert::AsRef::<str>::as_ref(other) });
+                (quote! { Self::#other(other) => #value, }, quote! { &str })
+            }
+            None => (quote! {}, quote! { &'static str }),
+        };
+        let to_str_flatten = self.flatten.iter().map(|(variant, _)| {
+            let value = wrap(quote! { ::roset::StrRepr::as_str(inner) });
+            quote! { Self::#variant(inner) => #value, }
+        });
+        if !self.flatten.is_empty() {
+            str_type = quote! { &str };
+        }
+
+        let covered = self.from_str.len() + self.other_variant.iter().count() + self.flatten.len();
+        let to_str_rest = (covered < self.variants.len()).then(|| quote! { _ => None, });
+        let str_type = match self.partial {
+            true => quote! { Option<#str_type> },
+            false => str_type,
+        };
+
+        let error_type = if self.fallible() {
+            quote! { #error_name }
+        } else {
+            quote! { ::core::convert::Infallible }
+        };
+        let fallback = self.write_flatten_arm(false);
+
+        // the first accessor holds the match, the others delegate to it
+        let vis = &self.vis;
+        let accessor = &self.accessors[0];
+        let str_array = self.has_str_array().then(|| {
+            let values: Vec<_> = self
+                .from_str
+                .iter()
+                .filter(|(variant, _)| !self.is_hidden(variant))
+                .map(|(variant, value)| self.str_tokens(variant, value))
+                .collect();
+            let count = values.len();
+            quote! {
+                /// Str of every variant but hidden ones in declaration order
+                #vis const VARIANT_STRS: [&'static str; #count] = [#(#values),*];
+            }
+        });
+        // fieldless enums index the array by ordinal, which folds to the discriminant,
+        // unless hidden variants leave gaps in it
+        let to_str_body = if self.has_str_array() && self.hidden.is_empty() {
+            let ordinals = self.variants.iter().enumerate().map(|(i, variant)| {
+                quote! { Self::#variant => #i }
+            });
+            quote! {
+                Self::VARIANT_STRS[match self {
+                    #(#ordinals,)*
+                }]
+            }
+        } else {
+            quote! {
+                match self {
+                    #(#to_str,)*
+                    #to_str_other
+                    #(#to_str_flatten)*
+                    #to_str_rest
+                }
+            }
+        };
+        let aliases = self.accessors[1..].iter().map(|alias| {
+            quote! {
+                #[inline]
+                #vis fn #alias(&self) -> #str_type {
+                    self.#accessor()
+                }
+            }
+        });
+
+        quote! {
+            #[allow(deprecated)]
+            impl ::std::str::FromStr for #enum_name {
+                type Err = #error_type;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    #from_str_id
+                    match s {
+                        #(#from_str,)*
+                        #(#from_alias,)*
+                        #(#from_ordinal,)*
+                        #fallback,
+                    }
+                }
+            }
+
+            #[allow(deprecated)]
+            impl #enum_name {
+                #str_array
+
+                #vis fn #accessor(&self) -> #str_type {
+                    #to_str_body
+                }
+
+                #(#aliases)*
+            }
+        }
+    }
+
+    /// `PartialEq` between the enum and `str`, `&str` both ways, a variant without str equals none
+    fn write_eq_str(&self) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let accessor = &self.accessors[0];
+        let eq = match self.partial {
+            false => quote! { self.#accessor() == other },
+            true => quote! { self.#accessor() == Some(other) },
+        };
+
+        quote! {
+            impl ::core::cmp::PartialEq<str> for #enum_name {
+                fn eq(&self, other: &str) -> bool {
+                    #eq
+                }
+            }
+
+            impl<'a> ::core::cmp::PartialEq<&'a str> for #enum_name {
+                fn eq(&self, other: &&'a str) -> bool {
+                    <Self as ::core::cmp::PartialEq<str>>::eq(self, *other)
+                }
+            }
+
+            impl ::core::cmp::PartialEq<#enum_name> for str {
+                fn eq(&self, other: &#enum_name) -> bool {
+                    <#enum_name as ::core::cmp::PartialEq<str>>::eq(other, self)
+                }
+            }
+
+            impl<'a> ::core::cmp::PartialEq<#enum_name> for &'a str {
+                fn eq(&self, other: &#enum_name) -> bool {
+                    <#enum_name as ::core::cmp::PartialEq<str>>::eq(other, *self)
+                }
+            }
+        }
+    }
+
+    /// `{enum}_as_str` module for `#[serde(with = "..")]` on fields, by str mapping
+    fn write_serde_as_str(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let accessor = &self.accessors[0];
+        let module = format_ident!("{}_as_str", to_snake_case(&enum_name.to_string()));
+        let doc = format!(
+            "Serialize `{}` as its str, for `#[serde(with = \"{}\")]` on fields",
+            enum_name, module
+        );
+        let expecting = format!("a string of {}", enum_name);
+
+        let serialize = match self.partial {
+            false => quote! { serializer.serialize_str(value.#accessor()) },
+            true => {
+                let reason = format!("variant of {} has no str", enum_name);
+                quote! {
+                    match value.#accessor() {
+                        Some(s) => serializer.serialize_str(s),
+                        None => Err(::serde::ser::Error::custom(#reason)),
+                    }
+                }
+            }
+        };
+
+        quote! {
+            #[doc = #doc]
+            #vis mod #module {
+                /// Serialize the value as its str
+                #[allow(deprecated)]
+                pub fn serialize<S>(value: &super::#enum_name, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: ::serde::Serializer,
+                {
+                    #serialize
+                }
+
+                /// Deserialize the value from its str
+                pub fn deserialize<'de, D>(deserializer: D) -> Result<super::#enum_name, D::Error>
+                where
+                    D: ::serde::Deserializer<'de>,
+                {
+                    struct Visitor;
+
+                    impl<'de> ::serde::de::Visitor<'de> for Visitor {
+                        type Value = super::#enum_name;
+
+                        fn expecting(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                            f.write_str(#expecting)
+                        }
+
+                        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+                        where
+                            E: ::serde::de::Error,
+                        {
+                            ::core::str::FromStr::from_str(value).map_err(E::custom)
+                        }
+                    }
+
+                    deserializer.deserialize_str(Visitor)
+                }
+            }
+        }
+    }
+
+    /// TypeScript union and JSON Schema of the str values but hidden ones, written by the macro
+    fn write_ts(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let strs: Vec<_> = self
+            .from_str
+            .iter()
+            .filter(|(variant, _)| !self.is_hidden(variant))
+            .map(|(_, value)| json_str(value))
+            .collect();
+        let ts_union = match strs.is_empty() {
+            true => "never".to_string(),
+            false => strs.join(" | "),
+        };
+        let json_schema = format!("{{\"type\":\"string\",\"enum\":[{}]}}", strs.join(","));
+
+        quote! {
+            impl #enum_name {
+                /// TypeScript union of the strs, like `"cat" | "dog"`
+                #vis fn ts_union() -> ::std::string::String {
+                    ::std::string::String::from(#ts_union)
+                }
+
+                /// JSON Schema of the strs, like `{"type":"string","enum":["cat","dog"]}`
+                #vis fn json_schema() -> ::std::string::String {
+                    ::std::string::String::from(#json_schema)
+                }
+            }
+        }
+    }
+
+    /// GraphQL impls delegating to a hidden mirror enum derived by async-graphql, whose items are
+    /// named by the strs
+    fn write_async_graphql(&self) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let enum_str = enum_name.to_string();
+        let mirror = format_ident!("__{}Graphql", enum_name);
+        let items = self.from_str.iter().map(|(variant, value)| {
+            quote! {
+                #[graphql(name = #value)]
+                #variant
+            }
+        });
+        let variants: Vec<_> = self.from_str.iter().map(|(variant, _)| variant).collect();
+
+        quote! {
+            #[doc(hidden)]
+            #[allow(non_camel_case_types)]
+            #[derive(Clone, Copy, PartialEq, Eq, ::async_graphql::Enum)]
+            #[graphql(name = #enum_str)]
+            enum #mirror {
+                #(#items,)*
+            }
+
+            #[allow(deprecated)]
+            impl #mirror {
+                fn new(value: &#enum_name) -> Self {
+                    match value {
+                        #(#enum_name::#variants => Self::#variants,)*
+                    }
+                }
+
+                fn get(self) -> #enum_name {
+                    match self {
+                        #(Self::#variants => #enum_name::#variants,)*
+                    }
+                }
+            }
+
+            impl ::async_graphql::InputType for #enum_name {
+                type RawValueType = Self;
+
+                fn type_name() -> ::std::borrow::Cow<'static, str> {
+                    <#mirror as ::async_graphql::InputType>::type_name()
+                }
+
+                fn create_type_info(registry: &mut ::async_graphql::registry::Registry) -> String {
+                    <#mirror as ::async_graphql::InputType>::create_type_info(registry)
+                }
+
+                fn parse(
+                    value: Option<::async_graphql::Value>,
+                ) -> ::async_graphql::InputValueResult<Self> {
+                    <#mirror as ::async_graphql::InputType>::parse(value)
+                        .map(#mirror::get)
+                        .map_err(::async_graphql::InputValueError::propagate)
+                }
+
+                fn to_value(&self) -> ::async_graphql::Value {
+                    ::async_graphql::InputType::to_value(&#mirror::new(self))
+                }
+
+                fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+                    Some(self)
+                }
+            }
+
+            impl ::async_graphql::OutputType for #enum_name {
+                fn type_name() -> ::std::borrow::Cow<'static, str> {
+                    <#mirror as ::async_graphql::OutputType>::type_name()
+                }
+
+                fn create_type_info(registry: &mut ::async_graphql::registry::Registry) -> String {
+                    <#mirror as ::async_graphql::OutputType>::create_type_info(registry)
+                }
+
+                async fn resolve(
+                    &self,
+                    ctx: &::async_graphql::ContextSelectionSet<'_>,
+                    field: &::async_graphql::Positioned<::async_graphql::parser::types::Field>,
+                ) -> ::async_graphql::ServerResult<::async_graphql::Value> {
+                    ::async_graphql::OutputType::resolve(&#mirror::new(self), ctx, field).await
+                }
+            }
+        }
+    }
+
+    /// `Deserialize` parsing a str, rejected input is reported with the expected str values
+    fn write_web(&self) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let expecting = format!("a string of {}", enum_name);
+        let expected = self
+            .from_str
+            .iter()
+            .filter(|(variant, _)| !self.is_hidden(variant))
+            .map(|(variant, value)| self.str_tokens(variant, value));
+
+        quote! {
+            const _: () = {
+                const EXPECTED: &[&str] = &[#(#expected),*];
+
+                struct Visitor;
+
+                impl<'de> ::serde::de::Visitor<'de> for Visitor {
+                    type Value = #enum_name;
+
+                    fn expecting(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        f.write_str(#expecting)
+                    }
+
+                    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+                    where
+                        E: ::serde::de::Error,
+                    {
+                        ::core::str::FromStr::from_str(value)
+                            .map_err(|_| E::unknown_variant(value, EXPECTED))
+                    }
+                }
+
+                impl<'de> ::serde::Deserialize<'de> for #enum_name {
+                    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                    where
+                        D: ::serde::Deserializer<'de>,
+                    {
+                        deserializer.deserialize_str(Visitor)
+                    }
+                }
+            };
+        }
+    }
+
+    /// `Serialize` and `Deserialize` through shadow enums derived by serde, renamed to the str values
+    /// and tagged internally, or adjacently with `serde_content`
+    fn write_serde_tagged(&self) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let tag = self.serde_tag.as_ref().unwrap();
+        let container = match &self.serde_content {
+            Some(content) => quote! { #[serde(tag = #tag, content = #content)] },
+            None => quote! { #[serde(tag = #tag)] },
+        };
+        let borrowed = self
+            .enum_data
+            .variants
+            .iter()
+            .any(|var| !var.fields.is_empty());
+        let lifetime = borrowed.then(|| quote! { <'a> });
+
+        let (mut shadows, mut owned, mut to_shadow, mut from_owned) =
+            (vec![], vec![], vec![], vec![]);
+        for (var, value) in self.from_str.iter() {
+            let fields = &self
+                .enum_data
+                .variants
+                .iter()
+                .find(|v| v.ident.eq(var))
+                .unwrap()
+                .fields;
+            let types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+            let (shadow, own, pattern) = match fields {
+                Fields::Unit => (quote! { #var }, quote! { #var }, quote! { #var }),
+                Fields::Unnamed(_) => {
+                    let bindings: Vec<_> = (0..types.len())
+                        .map(|i| format_ident!("field{}", i))
+                        .collect();
+                    (
+                        quote! { #var(#(&'a #types),*) },
+                        quote! { #var(#(#types),*) },
+                        quote! { #var(#(#bindings),*) },
+                    )
+                }
+                Fields::Named(_) => {
+                    let names: Vec<_> = fields.iter().map(|field| &field.ident).collect();
+                    (
+                        quote! { #var { #(#names: &'a #types),* } },
+                        quote! { #var { #(#names: #types),* } },
+                        quote! { #var { #(#names),* } },
+                    )
+                }
+            };
+            shadows.push(quote! { #[serde(rename = #value)] #shadow });
+            owned.push(quote! { #[serde(rename = #value)] #own });
+            to_shadow.push(quote! { #enum_name::#pattern => __RosetShadow::#pattern });
+            from_owned.push(quote! { __RosetOwned::#pattern => #enum_name::#pattern });
+        }
+
+        quote! {
+            #[allow(deprecated)]
+            const _: () = {
+                #[derive(::serde::Serialize)]
+                #container
+                enum __RosetShadow #lifetime {
+                    #(#shadows,)*
+                }
+
+                #[derive(::serde::Deserialize)]
+                #container
+                enum __RosetOwned {
+                    #(#owned,)*
+                }
+
+                impl ::serde::Serialize for #enum_name {
+                    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                    where
+                        S: ::serde::Serializer,
+                    {
+                        let shadow = match self {
+                            #(#to_shadow,)*
+                        };
+                        ::serde::Serialize::serialize(&shadow, serializer)
+                    }
+                }
+
+                impl<'de> ::serde::Deserialize<'de> for #enum_name {
+                    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                    where
+                        D: ::serde::Deserializer<'de>,
+                    {
+                        let owned = <__RosetOwned as ::serde::Deserialize>::deserialize(deserializer)?;
+                        Ok(match owned {
+                            #(#from_owned,)*
+                        })
+                    }
+                }
+            };
+        }
+    }
+
+    /// `str_id`, `from_str_id` and `str_id_of`, numbering str values in declaration order
+    fn write_str_ids(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+
+        let variants: Vec<_> = self.from_str.iter().map(|(variant, _)| variant).collect();
+        let values = self
+            .from_str
+            .iter()
+            .map(|(variant, value)| self.str_tokens(variant, value));
+        let ids: Vec<_> = (0..self.from_str.len() as u32).collect();
+        let constructs = variants.iter().map(|variant| self.write_construct(variant));
+
+        quote! {
+            #[allow(deprecated)]
+            impl #enum_name {
+                /// Returns the id of the variant str, compact to store and compare
+                #vis const fn str_id(&self) -> u32 {
+                    match self {
+                        #(Self::#variants { .. } => #ids,)*
+                    }
+                }
+
+                /// Returns the id of the str, if it is the str of a variant
+                #vis fn str_id_of(s: &str) -> Option<u32> {
+                    match s {
+                        #(#values => Some(#ids),)*
+                        _ => None,
+                    }
+                }
+
+                /// Returns the variant with the str id, if any
+                #vis fn from_str_id(id: u32) -> Option<Self> {
+                    match id {
+                        #(#ids => Some(#constructs),)*
+                        _ => None,
+                    }
+                }
+            }
+        }
+    }
+
+    /// `TryFrom<&HeaderValue>` and `From<Enum> for HeaderValue`, str values must be header safe
+    fn write_http(&self) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let error_name = self.parse_error_name();
+        let accessor = &self.accessors[0];
+
+        quote! {
+            #[allow(deprecated)]
+            impl<'a> ::std::convert::TryFrom<&'a ::http::HeaderValue> for #enum_name {
+                type Error = #error_name;
+
+                fn try_from(value: &'a ::http::HeaderValue) -> Result<Self, Self::Error> {
+                    match ::core::str::from_utf8(value.as_bytes()) {
+                        Ok(s) => ::std::str::FromStr::from_str(s).map_err(Into::into),
+                        Err(_) => Err(#error_name::InvalidUtf8),
+                    }
+                }
+            }
+
+            #[allow(deprecated)]
+            impl ::core::convert::From<#enum_name> for ::http::HeaderValue {
+                fn from(value: #enum_name) -> Self {
+                    ::http::HeaderValue::from_static(value.#accessor())
+                }
+            }
+        }
+    }
+
+    /// `defmt::Format` writing the str as format string, which defmt interns at compile time
+    fn write_defmt(&self) -> TokenStream {
+        let enum_name = &self.enum_name;
+
+        // str consts are written as argument, not interned
+        let arms = self.from_str.iter().map(|(var, value)| {
+            if self.is_str_const(var) {
+                let value = self.str_tokens(var, value);
+                return quote! { #enum_name::#var { .. } => ::defmt::write!(f, "{=str}", #value) };
+            }
+            let format = value.replace('{', "{{").replace('}', "}}");
+            quote! { #enum_name::#var { .. } => ::defmt::write!(f, #format) }
+        });
+
+        quote! {
+            #[allow(deprecated)]
+            impl ::defmt::Format for #enum_name {
+                fn format(&self, f: ::defmt::Formatter<'_>) {
+                    match self {
+                        #(#arms,)*
+                    }
+                }
+            }
+        }
+    }
+
+    /// `From<Enum>` for a string type and `TryFrom` back through `FromStr`
+    fn write_string_type(&self, string_type: &str) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let accessor = &self.accessors[0];
+        let string = string_type_path(string_type);
+
+        quote! {
+            #[allow(deprecated)]
+            impl ::core::convert::From<#enum_name> for #string {
+                fn from(value: #enum_name) -> Self {
+                    #string::new(value.#accessor())
+                }
+            }
+
+            #[allow(deprecated)]
+            impl<'a> ::std::convert::TryFrom<&'a #string> for #enum_name {
+                type Error = <Self as ::std::str::FromStr>::Err;
+
+                fn try_from(value: &'a #string) -> Result<Self, Self::Error> {
+                    ::std::str::FromStr::from_str(value.as_str())
+                }
+            }
+
+            #[allow(deprecated)]
+            impl ::std::convert::TryFrom<#string> for #enum_name {
+                type Error = <Self as ::std::str::FromStr>::Err;
+
+                fn try_from(value: #string) -> Result<Self, Self::Error> {
+                    Self::try_from(&value)
+                }
+            }
+        }
+    }
+
+    /// `TryFrom` the value type of a data format crate and `From` the enum back, by str mapping
+    fn write_value_format(&self, format: &str) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let error_name = self.parse_error_name();
+        let accessor = &self.accessors[0];
+        let (value, string, number, null) = value_format_paths(format);
+
+        let from_number = self.accept_ordinal.then(|| {
+            quote! {
+                #number(number) => {
+                    ::std::str::FromStr::from_str(&number.to_string()).map_err(Into::into)
+                }
+            }
+        });
+        let to_value = match (self.partial, null) {
+            (false, _) => quote! { #string(value.#accessor().into()) },
+            (true, Some(null)) => quote! {
+                match value.#accessor() {
+                    Some(s) => #string(s.into()),
+                    None => #null,
+                }
+            },
+            (true, None) => unreachable!(),
+        };
+
+        quote! {
+            #[allow(deprecated)]
+            impl<'a> ::std::convert::TryFrom<&'a #value> for #enum_name {
+                type Error = #error_name;
+
+                fn try_from(value: &'a #value) -> Result<Self, Self::Error> {
+                    match value {
+                        #string(s) => ::std::str::FromStr::from_str(s).map_err(Into::into),
+                        #from_number
+                        _ => Err(#error_name::UnexpectedType),
+                    }
+                }
+            }
+
+            #[allow(deprecated)]
+            impl ::std::convert::TryFrom<#value> for #enum_name {
+                type Error = #error_name;
+
+                fn try_from(value: #value) -> Result<Self, Self::Error> {
+                    Self::try_from(&value)
+                }
+            }
+
+            #[allow(deprecated)]
+            impl ::core::convert::From<#enum_name> for #value {
+                fn from(value: #enum_name) -> Self {
+                    #to_value
+                }
+            }
+        }
+    }
+
+    fn write_from_os_str(&self) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let error_name = self.parse_error_name();
+
+        quote! {
+            #[allow(deprecated)]
+            impl<'a> ::std::convert::TryFrom<&'a ::std::ffi::OsStr> for #enum_name {
+                type Error = #error_name;
+
+                fn try_from(s: &'a ::std::ffi::OsStr) -> Result<Self, Self::Error> {
+                    let s = s.to_str().ok_or(#error_name::InvalidUtf8)?;
+                    ::std::str::FromStr::from_str(s).map_err(Into::into)
+                }
+            }
+        }
+    }
+
+    fn write_from_bytes(&self) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let error_name = self.parse_error_name();
+        let from_bytes = self.str_arms().into_iter().map(|(variant, value)| {
+            if self.is_str_const(variant) {
+                let value = self.str_tokens(variant, value);
+                let variant = self.write_construct(variant);
+                return quote! { s if s == #value.as_bytes() => Ok(#variant) };
+            }
+
+            let value = LitByteStr::new(value.as_bytes(), Span::call_site());
+            let variant = self.write_construct(variant);
+            quote! { #value => Ok(#variant) }
+        });
+        let (from_bytes, table): (Vec<_>, _) = match self.table {
+            true => (vec![], Some(self.write_table_lookup(true))),
+            false => (from_bytes.collect(), None),
+        };
+        let fallback = self.write_flatten_arm(true);
+
+        quote! {
+            #[allow(deprecated)]
+            impl<'a> ::std::convert::TryFrom<&'a [u8]> for #enum_name {
+                type Error = #error_name;
+
+                fn try_from(s: &'a [u8]) -> Result<Self, Self::Error> {
+                    #table
+                    match s {
+                        #(#from_bytes,)*
+                        #fallback,
+                    }
+                }
+            }
+        }
+    }
+
+    /// `From` source type of an inner variant, a tuple for several fields, its binding pattern
+    /// and the variant built from it, fields with `skip_field` take `Default::default()`
+    fn inner_payload(&self, var: &Variant) -> (TokenStream, TokenStream, TokenStream) {
+        let skipped = self
+            .skip_fields
+            .iter()
+            .find(|(var_name, _)| var_name.eq(&var.ident))
+            .map(|(_, skipped)| skipped.as_slice())
+            .unwrap_or_default();
+
+        let (mut types, mut bindings, mut values) = (vec![], vec![], vec![]);
+        for (i, field) in var.fields.iter().enumerate() {
+            if skipped.contains(&i) {
+                values.push(quote! { ::core::default::Default::default() });
+                continue;
+            }
+            let binding = format_ident!("field{}", i);
+            types.push(&field.ty);
+            values.push(quote! { #binding });
+            bindings.push(binding);
+        }
+
+        let (source, pattern) = match types.len() {
+            1 => (quote! { #(#types)* }, quote! { #(#bindings)* }),
+            _ => (quote! { (#(#types,)*) }, quote! { (#(#bindings,)*) }),
+        };
+        let var_name = &var.ident;
+        let construct = match &var.fields {
+            Fields::Named(fields) => {
+                let names = fields.named.iter().map(|field| &field.ident);
+                quote! { Self::#var_name { #(#names: #values),* } }
+            }
+            _ => quote! { Self::#var_name(#(#values),*) },
+        };
+        (source, pattern, construct)
+    }
+
+    fn write_from_inner(&self) -> Vec<TokenStream> {
+        let enum_name = &self.enum_name;
+        self.from_inner_enum
+            .iter()
+            .map(|var_name| {
+                let var = self
+                    .enum_data
+                    .variants
+                    .iter()
+                    .find(|var| var.ident.eq(var_name))
+                    .unwrap();
+                let (source, pattern, construct) = self.inner_payload(var);
+
+                quote! {
+                    #[allow(deprecated)]
+                    impl From<#source> for #enum_name {
+                        fn from(#pattern: #source) -> Self {
+                            #construct
+                        }
+                    }
+                }
+            })
+            .chain(self.inner_from.iter().map(|(var_name, source)| {
+                let var = self
+                    .enum_data
+                    .variants
+                    .iter()
+                    .find(|var| var.ident.eq(var_name))
+                    .unwrap();
+                let (payload, pattern, construct) = self.inner_payload(var);
+
+                quote! {
+                    #[allow(deprecated)]
+                    impl From<#source> for #enum_name {
+                        fn from(value: #source) -> Self {
+                            let #pattern: #payload = ::core::convert::Into::into(value);
+                            #construct
+                        }
+                    }
+                }
+            }))
+            .collect()
+    }
+}
+
+/// Translate `strum(to_string = "..")` or else the first `strum(serialize = "..")` to `str`,
+/// the other `serialize` to aliases and `strum(default)` to `other`, strum owns the other keys
+fn parse_strum_attributes(
+    var_name: &Ident,
+    attrs: &[Attribute],
+    has_str: bool,
+) -> Result<(Vec<AttrItem>, Vec<String>)> {
+    let items = parse_all_items("strum", attrs)?;
+    let mut strs = vec![];
+    for item in items.iter() {
+        match item.key.to_string().as_str() {
+            "to_string" => strs.insert(0, item),
+            "serialize" => strs.push(item),
+            _ => {}
+        }
+    }
+    let to_string = items.iter().filter(|item| item.is("to_string")).nth(1);
+    if let Some(item) = to_string {
+        let reason = format!("{}: strum(to_string) used twice on the variant", var_name);
+        return Err(Error::new(item.span(), reason));
+    }
+
+    let mut translated: Vec<_> = items
+        .iter()
+        .filter(|item| item.is("default"))
+        .map(|item| item.renamed("other"))
+        .collect();
+    let mut aliases = vec![];
+    if !has_str {
+        if let Some((first, rest)) = strs.split_first() {
+            translated.push(first.renamed("str"));
+            for item in rest {
+                aliases.push(item.str_value()?.value());
+            }
+        }
+    }
+    Ok((translated, aliases))
+}
+
+/// Path of the string type enabled by `smol_str` or `compact_str`
+fn string_type_path(string_type: &str) -> TokenStream {
+    match string_type {
+        "smol_str" => quote! { ::smol_str::SmolStr },
+        "compact_str" => quote! { ::compact_str::CompactString },
+        _ => unreachable!(),
+    }
+}
+
+/// Paths of the value type of a data format and its string, number and null variants
+fn value_format_paths(
+    format: &str,
+) -> (TokenStream, TokenStream, TokenStream, Option<TokenStream>) {
+    match format {
+        "json" => (
+            quote! { ::serde_json::Value },
+            quote! { ::serde_json::Value::String },
+            quote! { ::serde_json::Value::Number },
+            Some(quote! { ::serde_json::Value::Null }),
+        ),
+        "toml" => (
+            quote! { ::toml::Value },
+            quote! { ::toml::Value::String },
+            quote! { ::toml::Value::Integer },
+            None,
+        ),
+        "yaml" => (
+            quote! { ::serde_yaml::Value },
+            quote! { ::serde_yaml::Value::String },
+            quote! { ::serde_yaml::Value::Number },
+            Some(quote! { ::serde_yaml::Value::Null }),
+        ),
+        _ => unreachable!(),
+    }
+}
+
+/// JSON string literal of `value`
+fn json_str(value: &str) -> String {
+    let mut json = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Split `error_msg` into text and the `{input}`, `{expected}` placeholders, `{{` and `}}` escape
+fn parse_error_msg(lit: &syn::LitStr) -> Result<Vec<MessagePart>> {
+    let (message, mut parts, mut text) = (lit.value(), vec![], String::new());
+    let mut rest = message.as_str();
+    while let Some(c) = rest.chars().next() {
+        if let Some(tail) = rest.strip_prefix("{{").or_else(|| rest.strip_prefix("}}")) {
+            text.push(c);
+            rest = tail;
+            continue;
+        }
+        if c == '}' {
+            return Err(Error::new(
+                lit.span(),
+                "error_msg has an unmatched `}`, write `}}`",
+            ));
+        }
+        if c != '{' {
+            text.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        let end = rest
+            .find('}')
+            .ok_or_else(|| Error::new(lit.span(), "error_msg has an unmatched `{`, write `{{`"))?;
+        let part = match &rest[1..end] {
+            "input" => MessagePart::Input,
+            "expected" => MessagePart::Expected,
+            name => {
+                let reason = format!(
+                    "error_msg placeholder {{{}}} is unknown, expected {{input}} or {{expected}}",
+                    name
+                );
+                return Err(Error::new(lit.span(), reason));
+            }
+        };
+        parts.push(MessagePart::Text(std::mem::take(&mut text)));
+        parts.push(part);
+        rest = &rest[end + 1..];
+    }
+    parts.push(MessagePart::Text(text));
+    Ok(parts)
+}
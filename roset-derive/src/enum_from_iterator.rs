@@ -0,0 +1,62 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Generics;
+use syn::Ident;
+use syn::Visibility;
+
+pub(crate) struct EnumFromIterator {
+    vis: Visibility,
+    enum_name: Ident,
+    generics: Generics,
+}
+
+impl EnumFromIterator {
+    pub fn new(vis: Visibility, enum_name: Ident, generics: Generics) -> Self {
+        EnumFromIterator {
+            vis,
+            enum_name,
+            generics,
+        }
+    }
+
+    /// `parse_list` and `join` built on `FromStr` and `StrRepr` of `EnumFrom`
+    pub fn write_output(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+
+        quote! {
+            #[allow(deprecated)]
+            impl #impl_generics #enum_name #ty_generics #where_clause {
+                /// Parses every item of `s` separated by `sep`, ignoring whitespace around items,
+                /// an empty or blank `s` is an empty list
+                #vis fn parse_list(
+                    s: &str,
+                    sep: char,
+                ) -> ::core::result::Result<
+                    ::std::vec::Vec<Self>,
+                    <Self as ::core::str::FromStr>::Err,
+                > {
+                    if s.trim().is_empty() {
+                        return Ok(::std::vec::Vec::new());
+                    }
+                    s.split(sep)
+                        .map(|item| ::core::str::FromStr::from_str(item.trim()))
+                        .collect()
+                }
+
+                /// Joins the str of every item with `sep`, the inverse of `parse_list`
+                #vis fn join(items: &[Self], sep: &str) -> ::std::string::String {
+                    let mut joined = ::std::string::String::new();
+                    for (i, item) in items.iter().enumerate() {
+                        if i > 0 {
+                            joined.push_str(sep);
+                        }
+                        joined.push_str(::roset::StrRepr::as_str(item));
+                    }
+                    joined
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,478 @@
+use crate::attr::is_attr;
+use crate::attr::parse_items;
+use crate::get_wrapped_unnamed;
+use crate::is_phantom;
+use crate::to_snake_case;
+use crate::type_key;
+use proc_macro2::TokenStream;
+use proc_macro2::TokenTree;
+use quote::format_ident;
+use quote::quote;
+use syn::Attribute;
+use syn::DataEnum;
+use syn::Error;
+use syn::Fields;
+use syn::Generics;
+use syn::Ident;
+use syn::LitStr;
+use syn::Result;
+use syn::Type;
+use syn::Variant;
+use syn::Visibility;
+
+const ENUM_ATTRIBUTES: &[&str] = &["const_fn", "newtype", "deref"];
+
+const VARIANT_ATTRIBUTES: &[&str] = &["transitive", "include_phantom"];
+
+pub(crate) struct EnumFromWrapped {
+    vis: Visibility,
+    enum_name: Ident,
+    enum_attrs: Vec<Attribute>,
+    generics: Generics,
+    enum_data: DataEnum,
+
+    /// `(variant, source type)` converted through the variant payload
+    transitive: Vec<(Ident, Type)>,
+    /// `Some` with `#[enum_error]`, display format string of each variant if given
+    error: Option<Vec<(Ident, Option<LitStr>)>>,
+    const_fn: bool,
+    /// Variants whose `From` impl would overlap another one
+    suppressed: Vec<Ident>,
+    /// Variants taking their `PhantomData` fields as payload instead of filling them
+    include_phantom: Vec<Ident>,
+    /// Single variant enum converted back into its payload and borrowed as it
+    newtype: bool,
+    /// `Deref` and `DerefMut` to the payload of a newtype enum
+    deref: bool,
+}
+
+impl EnumFromWrapped {
+    pub fn new(
+        vis: Visibility,
+        enum_name: Ident,
+        enum_attrs: Vec<Attribute>,
+        generics: Generics,
+        enum_data: DataEnum,
+    ) -> Self {
+        EnumFromWrapped {
+            vis,
+            enum_name,
+            enum_attrs,
+            generics,
+            enum_data,
+            transitive: vec![],
+            error: None,
+            const_fn: false,
+            suppressed: vec![],
+            include_phantom: vec![],
+            newtype: false,
+            deref: false,
+        }
+    }
+
+    pub fn parse_attributes(&mut self) -> Result<()> {
+        for item in parse_items("enum_from_wrapped", &self.enum_attrs, ENUM_ATTRIBUTES)? {
+            item.flag()?;
+            match item.key.to_string().as_str() {
+                "const_fn" => self.const_fn = true,
+                "newtype" => self.newtype = true,
+                "deref" => self.deref = true,
+                _ => unreachable!(),
+            }
+        }
+        self.check_newtype()?;
+        for var in self.enum_data.variants.iter() {
+            for item in parse_items("enum_from_wrapped", &var.attrs, VARIANT_ATTRIBUTES)? {
+                if item.is("include_phantom") {
+                    item.flag()?;
+                    self.include_phantom.push(var.ident.clone());
+                }
+            }
+        }
+        self.find_conflicts();
+        self.parse_transitive()?;
+        self.parse_error()
+    }
+
+    fn check_newtype(&self) -> Result<()> {
+        if !self.newtype && !self.deref {
+            return Ok(());
+        }
+
+        let title = format!("{}: newtype and deref", self.enum_name);
+        let variants = &self.enum_data.variants;
+        if variants.len() != 1 || variants[0].fields.len() != 1 {
+            let reason = format!("{} require one variant with one payload field", title);
+            return Err(Error::new(self.enum_name.span(), reason));
+        }
+        // `impl<T> From<Enum<T>> for T` is rejected by the orphan rules
+        let key = type_key(&self.payload(&variants[0]).0);
+        if self.newtype && self.generics.type_params().any(|param| param.ident == key) {
+            let reason = format!("{} can not convert back into a bare type parameter", title);
+            return Err(Error::new(variants[0].ident.span(), reason));
+        }
+        Ok(())
+    }
+
+    /// Variants with a payload, unit and `PhantomData` only variants get no conversion
+    fn wrapped_variants(&self) -> impl Iterator<Item = &Variant> {
+        self.enum_data.variants.iter().filter(|var| {
+            !matches!(var.fields, Fields::Unit) && !self.payload_fields(var).is_empty()
+        })
+    }
+
+    /// Positions of the payload fields, `PhantomData` fields are filled in instead
+    fn payload_fields(&self, var: &Variant) -> Vec<usize> {
+        let include = self.include_phantom.contains(&var.ident);
+        var.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| include || !is_phantom(&field.ty))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Suppress impls that would overlap: payloads of the same type, and a bare generic
+    /// parameter payload like `Some(T)` beside a payload not mentioning that parameter
+    fn find_conflicts(&mut self) {
+        let payloads: Vec<_> = self
+            .wrapped_variants()
+            .map(|var| (var.ident.clone(), self.payload(var).0))
+            .collect();
+        let params: Vec<_> = self
+            .generics
+            .type_params()
+            .map(|param| &param.ident)
+            .collect();
+
+        for (i, (var_name, payload)) in payloads.iter().enumerate() {
+            let others = payloads
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| i != *j)
+                .map(|(_, (_, other))| other);
+
+            let key = type_key(payload);
+            let bare_param = params.iter().find(|param| key == param.to_string());
+            let duplicate = others.clone().any(|other| type_key(other) == key);
+            let overlap = bare_param
+                .is_some_and(|param| others.clone().any(|other| !mentions(other.clone(), param)));
+
+            if duplicate || overlap {
+                self.suppressed.push(var_name.clone());
+            }
+        }
+    }
+
+    fn parse_error(&mut self) -> Result<()> {
+        match self
+            .enum_attrs
+            .iter()
+            .find(|attr| is_attr(attr, "enum_error"))
+        {
+            Some(attr) if !attr.tokens.is_empty() => {
+                let reason = "enum_error on the enum takes no arguments";
+                return Err(Error::new_spanned(attr, reason));
+            }
+            Some(_) => {}
+            None => return Ok(()),
+        }
+
+        let mut displays = vec![];
+        for var in self.enum_data.variants.iter() {
+            if var.fields.len() != 1 {
+                let reason = format!("{}: enum_error requires one payload field", var.ident);
+                return Err(Error::new(var.ident.span(), reason));
+            }
+
+            let mut display = None;
+            for item in parse_items("enum_error", &var.attrs, &["display"])? {
+                display = Some(item.str_value()?.clone());
+            }
+            displays.push((var.ident.clone(), display));
+        }
+        self.error = Some(displays);
+        Ok(())
+    }
+
+    fn parse_transitive(&mut self) -> Result<()> {
+        let enum_name = self.enum_name.to_string();
+        let mut seen = vec![enum_name.clone(), "Self".to_string()];
+        for var in self.wrapped_variants() {
+            seen.push(type_key(&self.payload(var).0));
+        }
+
+        for var in self.enum_data.variants.iter() {
+            for item in parse_items("enum_from_wrapped", &var.attrs, VARIANT_ATTRIBUTES)? {
+                if !item.is("transitive") {
+                    continue;
+                }
+                let source: Type = item.str_value()?.parse()?;
+                if var.fields.len() != 1 {
+                    let reason = "transitive attribute requires one payload field";
+                    return Err(Error::new(item.span(), reason));
+                }
+
+                // a source already converted elsewhere, or the enum itself, would loop or conflict
+                let key = type_key(&source);
+                if seen.contains(&key) {
+                    let reason = format!(
+                        "{}::{}: transitive From<{}> conflicts with another conversion of {}",
+                        enum_name, var.ident, key, enum_name
+                    );
+                    return Err(Error::new(item.span(), reason));
+                }
+                seen.push(key);
+                self.transitive.push((var.ident.clone(), source));
+            }
+        }
+        Ok(())
+    }
+
+    /// Payload type, a tuple for several fields, its binding pattern and the variant built from it
+    fn payload(&self, var: &Variant) -> (TokenStream, TokenStream, TokenStream) {
+        let var_name = &var.ident;
+        let wrapped = get_wrapped_unnamed("EnumFromWrapped", &self.enum_name, var.fields.clone());
+        let kept = self.payload_fields(var);
+        let binding = |i: usize| match kept.len() {
+            1 => format_ident!("inner"),
+            _ => format_ident!("field{}", i),
+        };
+
+        let types: Vec<_> = kept.iter().map(|i| &wrapped[*i].ty).collect();
+        let bindings: Vec<_> = kept.iter().map(|i| binding(*i)).collect();
+        let values = (0..wrapped.len()).map(|i| match kept.contains(&i) {
+            true => {
+                let binding = binding(i);
+                quote! { #binding }
+            }
+            false => quote! { ::core::marker::PhantomData },
+        });
+        let construct = quote! { Self::#var_name(#(#values),*) };
+        match kept.len() {
+            1 => (quote! { #(#types)* }, quote! { #(#bindings)* }, construct),
+            _ => (
+                quote! { (#(#types,)*) },
+                quote! { (#(#bindings,)*) },
+                construct,
+            ),
+        }
+    }
+
+    pub fn write_output(&self) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        let direct = self
+            .wrapped_variants()
+            .filter(|var| !self.suppressed.contains(&var.ident))
+            .map(|var| {
+                let (wrapped, pattern, construct) = self.payload(var);
+
+                quote! {
+                    #[allow(deprecated)]
+                    impl #impl_generics From<#wrapped> for #enum_name #ty_generics #where_clause {
+                        fn from(#pattern: #wrapped) -> Self {
+                            #construct
+                        }
+                    }
+                }
+            });
+
+        let transitive = self.transitive.iter().map(|(var_name, source)| {
+            quote! {
+                #[allow(deprecated)]
+                impl #impl_generics From<#source> for #enum_name #ty_generics #where_clause {
+                    fn from(inner: #source) -> Self {
+                        Self::#var_name(::core::convert::From::from(inner))
+                    }
+                }
+            }
+        });
+
+        let error = self.write_error();
+        let const_fn = self.write_const_fn();
+        let newtype = self.write_newtype();
+        quote! {
+            #(#direct)*
+            #(#transitive)*
+            #error
+            #const_fn
+            #newtype
+        }
+    }
+
+    /// Conversion back into the payload with `AsRef` and `AsMut`, and `Deref` if asked for
+    fn write_newtype(&self) -> TokenStream {
+        if !self.newtype && !self.deref {
+            return TokenStream::new();
+        }
+
+        let enum_name = &self.enum_name;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        let var_name = &self.enum_data.variants[0].ident;
+        let wrapped = self.payload(&self.enum_data.variants[0]).0;
+
+        let newtype = self.newtype.then(|| {
+            quote! {
+                #[allow(deprecated)]
+                impl #impl_generics From<#enum_name #ty_generics> for #wrapped #where_clause {
+                    fn from(value: #enum_name #ty_generics) -> Self {
+                        match value {
+                            #enum_name::#var_name(inner) => inner,
+                        }
+                    }
+                }
+
+                #[allow(deprecated)]
+                impl #impl_generics ::core::convert::AsRef<#wrapped> for #enum_name #ty_generics #where_clause {
+                    fn as_ref(&self) -> &#wrapped {
+                        match self {
+                            #enum_name::#var_name(inner) => inner,
+                        }
+                    }
+                }
+
+                #[allow(deprecated)]
+                impl #impl_generics ::core::convert::AsMut<#wrapped> for #enum_name #ty_generics #where_clause {
+                    fn as_mut(&mut self) -> &mut #wrapped {
+                        match self {
+                            #enum_name::#var_name(inner) => inner,
+                        }
+                    }
+                }
+            }
+        });
+        let deref = self.deref.then(|| {
+            quote! {
+                #[allow(deprecated)]
+                impl #impl_generics ::core::ops::Deref for #enum_name #ty_generics #where_clause {
+                    type Target = #wrapped;
+
+                    fn deref(&self) -> &Self::Target {
+                        match self {
+                            #enum_name::#var_name(inner) => inner,
+                        }
+                    }
+                }
+
+                #[allow(deprecated)]
+                impl #impl_generics ::core::ops::DerefMut for #enum_name #ty_generics #where_clause {
+                    fn deref_mut(&mut self) -> &mut Self::Target {
+                        match self {
+                            #enum_name::#var_name(inner) => inner,
+                        }
+                    }
+                }
+            }
+        });
+
+        quote! {
+            #newtype
+            #deref
+        }
+    }
+
+    /// `const fn from_{variant}` constructors, as `From::from` can not be called in const
+    fn write_const_fn(&self) -> TokenStream {
+        if !self.const_fn {
+            return TokenStream::new();
+        }
+
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        let constructors = self.wrapped_variants().map(|var| {
+            let var_name = &var.ident;
+            // const fn can not take a tuple with drop apart, so fields are separate arguments
+            let wrapped = get_wrapped_unnamed("EnumFromWrapped", enum_name, var.fields.clone());
+            let kept = self.payload_fields(var);
+            let params = kept.iter().map(|i| {
+                let ty = &wrapped[*i].ty;
+                match kept.len() {
+                    1 => quote! { inner: #ty },
+                    _ => {
+                        let field = format_ident!("field{}", i);
+                        quote! { #field: #ty }
+                    }
+                }
+            });
+            let construct = self.payload(var).2;
+            let name = format_ident!("from_{}", to_snake_case(&var_name.to_string()));
+            let doc = format!("Wrap the payload into `{}::{}`", enum_name, var_name);
+
+            quote! {
+                #[doc = #doc]
+                #[must_use]
+                #[inline]
+                #[allow(clippy::too_many_arguments)]
+                #vis const fn #name(#(#params),*) -> Self {
+                    #construct
+                }
+            }
+        });
+
+        quote! {
+            #[allow(deprecated)]
+            impl #impl_generics #enum_name #ty_generics #where_clause {
+                #(#constructors)*
+            }
+        }
+    }
+
+    /// `Display` and `Error` impls, variants without display format are transparent
+    fn write_error(&self) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        let displays = match &self.error {
+            Some(displays) => displays,
+            None => return TokenStream::new(),
+        };
+
+        let display = displays.iter().map(|(var_name, display)| match display {
+            // `{0}` refers to the payload like in tuple struct formatting
+            Some(format) if uses_payload(format) => quote! {
+                Self::#var_name(inner) => ::core::write!(f, #format, inner)
+            },
+            Some(format) => quote! { Self::#var_name(_) => f.write_str(#format) },
+            None => quote! { Self::#var_name(inner) => ::core::fmt::Display::fmt(inner, f) },
+        });
+        let source = displays.iter().map(|(var_name, display)| match display {
+            Some(_) => quote! { Self::#var_name(inner) => Some(inner) },
+            None => quote! { Self::#var_name(inner) => ::core::error::Error::source(inner) },
+        });
+
+        quote! {
+            #[allow(deprecated)]
+            impl #impl_generics ::core::fmt::Display for #enum_name #ty_generics #where_clause {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match self {
+                        #(#display,)*
+                    }
+                }
+            }
+
+            #[allow(deprecated)]
+            impl #impl_generics ::core::error::Error for #enum_name #ty_generics #where_clause {
+                fn source(&self) -> Option<&(dyn ::core::error::Error + 'static)> {
+                    match self {
+                        #(#source,)*
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn uses_payload(format: &LitStr) -> bool {
+    let format = format.value().replace("{{", "");
+    ["{0", "{}", "{:"].iter().any(|arg| format.contains(arg))
+}
+
+/// Whether the ident appears anywhere in the tokens
+fn mentions(tokens: TokenStream, ident: &Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(other) => other.eq(ident),
+        TokenTree::Group(group) => mentions(group.stream(), ident),
+        _ => false,
+    })
+}
@@ -0,0 +1,160 @@
+use crate::options::RosetOptions;
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::Attribute;
+use syn::DataEnum;
+use syn::Error;
+use syn::Fields;
+use syn::Ident;
+use syn::Result;
+use syn::Visibility;
+
+pub(crate) struct EnumIntoStaticSet {
+    vis: Visibility,
+    enum_name: Ident,
+    enum_attrs: Vec<Attribute>,
+    enum_data: DataEnum,
+
+    set_name: Ident,
+    /// Smallest unsigned integer with a bit for every variant
+    bits_type: Ident,
+}
+
+impl EnumIntoStaticSet {
+    pub fn new(
+        vis: Visibility,
+        enum_name: Ident,
+        enum_attrs: Vec<Attribute>,
+        enum_data: DataEnum,
+    ) -> Self {
+        EnumIntoStaticSet {
+            set_name: format_ident!("{}StaticSet", enum_name),
+            bits_type: format_ident!("u8"),
+            vis,
+            enum_name,
+            enum_attrs,
+            enum_data,
+        }
+    }
+
+    pub fn parse_attributes(&mut self) -> Result<()> {
+        if self.enum_data.variants.is_empty() {
+            let reason = format!(
+                "{}: EnumIntoStaticSet requires at least one variant",
+                self.enum_name
+            );
+            return Err(Error::new(self.enum_name.span(), reason));
+        }
+        for var in self.enum_data.variants.iter() {
+            if !matches!(var.fields, Fields::Unit) {
+                let reason = format!(
+                    "{}: EnumIntoStaticSet only support unit variant",
+                    self.enum_name
+                );
+                return Err(Error::new(var.ident.span(), reason));
+            }
+        }
+        self.bits_type = match self.enum_data.variants.len() {
+            0..=8 => format_ident!("u8"),
+            9..=16 => format_ident!("u16"),
+            17..=32 => format_ident!("u32"),
+            33..=64 => format_ident!("u64"),
+            65..=128 => format_ident!("u128"),
+            _ => {
+                let reason = format!(
+                    "{}: EnumIntoStaticSet supports at most 128 variants",
+                    self.enum_name
+                );
+                return Err(Error::new(self.enum_name.span(), reason));
+            }
+        };
+        self.set_name =
+            RosetOptions::parse(&self.enum_attrs)?.name("static_set_name", self.set_name.clone());
+        Ok(())
+    }
+
+    /// `{Enum}StaticSet` bitmask built in const contexts, `is_one_of` and a `{enum}_set!` macro
+    pub fn write_output(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let set_name = &self.set_name;
+        let bits_type = &self.bits_type;
+        let macro_name = format_ident!("{}_set", crate::to_snake_case(&enum_name.to_string()));
+        let doc = format!(
+            "Set of `{}` variants as a bitmask, built in const contexts with `{}!`",
+            enum_name, macro_name
+        );
+
+        let bits = self.enum_data.variants.iter().enumerate().map(|(i, var)| {
+            let var = &var.ident;
+            quote! { #enum_name::#var => 1 << #i }
+        });
+
+        quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+            #vis struct #set_name {
+                bits: #bits_type,
+            }
+
+            /// Builds the static set of the listed variants, usable in `const` items, the enum and the
+            /// set type must be in scope at the call site
+            #[allow(unused_macros)]
+            macro_rules! #macro_name {
+                ($($variant:ident),* $(,)?) => {
+                    #set_name::of(&[$(#enum_name::$variant),*])
+                };
+            }
+
+            #[allow(deprecated)]
+            impl #set_name {
+                const fn bit(variant: &#enum_name) -> #bits_type {
+                    match variant {
+                        #(#bits,)*
+                    }
+                }
+
+                /// Create the set of `variants`
+                #vis const fn of(variants: &[#enum_name]) -> Self {
+                    let mut bits = 0;
+                    let mut i = 0;
+                    while i < variants.len() {
+                        bits |= Self::bit(&variants[i]);
+                        i += 1;
+                    }
+                    #set_name { bits }
+                }
+
+                /// Returns the raw bits, the bit index is the variant ordinal
+                #vis const fn bits(&self) -> #bits_type {
+                    self.bits
+                }
+
+                /// Returns `true` if the set contains the variant, a single mask test
+                #vis const fn contains(&self, variant: &#enum_name) -> bool {
+                    self.bits & Self::bit(variant) != 0
+                }
+
+                /// Set of the variants in either set
+                #vis const fn union(self, other: Self) -> Self {
+                    #set_name { bits: self.bits | other.bits }
+                }
+            }
+
+            #[allow(deprecated)]
+            impl #enum_name {
+                /// Returns `true` if the variant is listed in `set`, a mask test once `set` is
+                /// known at compile time
+                #vis const fn is_one_of(&self, set: &[Self]) -> bool {
+                    #set_name::of(set).contains(self)
+                }
+
+                /// Returns `true` if the variant is in the static set
+                #vis const fn is_in(&self, set: #set_name) -> bool {
+                    set.contains(self)
+                }
+            }
+        }
+    }
+}
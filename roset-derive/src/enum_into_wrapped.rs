@@ -0,0 +1,431 @@
+use crate::attr::parse_items;
+use crate::get_deprecated;
+use crate::get_doc_attrs;
+use crate::get_wrapped_unnamed;
+use crate::options::RosetOptions;
+use crate::to_snake_case;
+use crate::type_key;
+use proc_macro2::Span;
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::Attribute;
+use syn::DataEnum;
+use syn::Error;
+use syn::Ident;
+use syn::Result;
+use syn::Visibility;
+
+const ENUM_ATTRIBUTES: &[&str] = &["compat_unit_error", "extractors"];
+
+const VARIANT_ATTRIBUTES: &[&str] = &["infallible", "into_option"];
+
+pub(crate) struct EnumIntoWrapped {
+    vis: Visibility,
+    enum_name: Ident,
+    enum_attrs: Vec<Attribute>,
+    enum_data: DataEnum,
+
+    /// Variant converted by an infallible `From<Enum>` instead of `TryFrom<Enum>`
+    infallible: Option<Ident>,
+    /// Convert the variant error into `()`, the error type before `{Enum}VariantError`
+    compat_unit_error: bool,
+    /// Variants converted into `Option` of their payload as well
+    into_option: Vec<Ident>,
+    /// Generate iterator filters down to the payloads of one variant
+    extractors: bool,
+    error_name: Ident,
+}
+
+impl EnumIntoWrapped {
+    pub fn new(
+        vis: Visibility,
+        enum_name: Ident,
+        enum_attrs: Vec<Attribute>,
+        enum_data: DataEnum,
+    ) -> Self {
+        EnumIntoWrapped {
+            error_name: format_ident!("{}VariantError", enum_name),
+            vis,
+            enum_name,
+            enum_attrs,
+            enum_data,
+            infallible: None,
+            compat_unit_error: false,
+            into_option: vec![],
+            extractors: false,
+        }
+    }
+
+    pub fn parse_attributes(&mut self) -> Result<()> {
+        self.error_name = RosetOptions::parse(&self.enum_attrs)?
+            .name("variant_error_name", self.error_name.clone());
+        for item in parse_items("enum_into_wrapped", &self.enum_attrs, ENUM_ATTRIBUTES)? {
+            item.flag()?;
+            match item.key.to_string().as_str() {
+                "compat_unit_error" => self.compat_unit_error = true,
+                "extractors" => self.extractors = true,
+                _ => unreachable!(),
+            }
+        }
+        let mut option_types = vec![];
+        for var in self.enum_data.variants.iter() {
+            for item in parse_items("enum_into_wrapped", &var.attrs, VARIANT_ATTRIBUTES)? {
+                item.flag()?;
+                if item.is("into_option") {
+                    // `From<Enum> for Option<T>` can be implemented once per payload type
+                    let key = type_key(&var.fields);
+                    if let Some((other, _)) = option_types.iter().find(|(_, other)| *other == key) {
+                        let reason =
+                            format!("into_option already used on {} of the same type", other);
+                        return Err(Error::new(item.span(), reason));
+                    }
+                    option_types.push((var.ident.clone(), key));
+                    self.into_option.push(var.ident.clone());
+                    continue;
+                }
+                if let Some(other) = &self.infallible {
+                    let reason = format!("infallible attribute already used on {}", other);
+                    return Err(Error::new(item.span(), reason));
+                }
+                self.infallible = Some(var.ident.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// `From<Enum>` for the infallible variant, other variants are guaranteed absent by the user
+    fn write_infallible(&self, var_name: &Ident, wrapped: TokenStream) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let error_name = self.variant_error_name();
+        let expected = var_name.to_string();
+        let other = (self.enum_data.variants.len() > 1).then(|| {
+            quote! { other => ::core::panic!("{}", #error_name::new(#expected, &other)), }
+        });
+
+        quote! {
+            #[allow(deprecated)]
+            impl ::core::convert::From<#enum_name> for #wrapped {
+                fn from(value: #enum_name) -> Self {
+                    match value {
+                        #enum_name::#var_name(res) => res,
+                        #other
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn write_output(&self) -> TokenStream {
+        let mut output = self.write_variant_error();
+        output.extend(self.enum_data.variants.iter().map(|var| {
+            let var_name = &var.ident;
+            let expected = var_name.to_string();
+            let enum_name = &self.enum_name;
+            let error_name = self.variant_error_name();
+            let wrapped = get_wrapped_unnamed("EnumIntoWrapped", enum_name, var.fields.clone());
+            let owned = match &self.infallible {
+                Some(infallible) if infallible.eq(var_name) => {
+                    self.write_infallible(var_name, quote! { #wrapped })
+                }
+                _ => quote! {
+                    #[allow(deprecated)]
+                    impl ::std::convert::TryFrom<#enum_name> for #wrapped {
+                        type Error = #error_name;
+
+                        fn try_from(value: #enum_name) -> ::core::result::Result<Self, Self::Error> {
+                            match value {
+                                #enum_name::#var_name(res) => Ok(res),
+                                other => Err(#error_name::new(#expected, &other)),
+                            }
+                        }
+                    }
+                },
+            };
+
+            let option = self.into_option.contains(var_name).then(|| {
+                quote! {
+                    #[allow(deprecated)]
+                    impl ::core::convert::From<#enum_name> for ::core::option::Option<#wrapped> {
+                        fn from(value: #enum_name) -> Self {
+                            match value {
+                                #enum_name::#var_name(res) => Some(res),
+                                #[allow(unreachable_patterns)]
+                                _ => None,
+                            }
+                        }
+                    }
+                }
+            });
+
+            quote! {
+                #owned
+                #option
+
+                // lifetime named apart from those in the payload, like `for<'a> Fn(&'a str)`
+                #[allow(deprecated)]
+                impl<'__roset> ::std::convert::TryFrom<&'__roset mut #enum_name> for &'__roset mut #wrapped {
+                    type Error = #error_name;
+
+                    fn try_from(value: &'__roset mut #enum_name) -> ::core::result::Result<Self, Self::Error> {
+                        match value {
+                            #enum_name::#var_name(res) => Ok(res),
+                            other => Err(#error_name::new(#expected, other)),
+                        }
+                    }
+                }
+            }
+        }));
+        output.extend(self.write_accessors());
+        if self.extractors {
+            output.extend(self.write_extractors());
+        }
+        output.extend(self.write_fold());
+        output
+    }
+
+    /// `is_*`, `as_*` and `try_into_*` per variant, carrying the variant docs and deprecation
+    fn write_accessors(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let accessors = self.enum_data.variants.iter().map(|var| {
+            let var_name = &var.ident;
+            let wrapped = get_wrapped_unnamed("EnumIntoWrapped", enum_name, var.fields.clone());
+            let snake = to_snake_case(&var_name.to_string());
+            let is_name = format_ident!("is_{}", snake);
+            let as_name = format_ident!("as_{}", snake);
+            let try_into_name = format_ident!("try_into_{}", snake);
+            let is_doc = format!(
+                "Returns `true` if the value is `{}::{}`",
+                enum_name, var_name
+            );
+            let as_doc = format!(
+                "Returns a reference to the inner value if the value is `{}::{}`",
+                enum_name, var_name
+            );
+            let try_into_doc = format!(
+                "Returns the inner value if the value is `{}::{}`, or the value itself otherwise",
+                enum_name, var_name
+            );
+
+            let docs = get_doc_attrs(&var.attrs);
+            let separator = (!docs.is_empty()).then(|| quote! { #[doc = ""] });
+            let deprecated = get_deprecated(&var.attrs);
+
+            quote! {
+                #[doc = #is_doc]
+                #separator
+                #(#docs)*
+                #deprecated
+                #vis fn #is_name(&self) -> bool {
+                    ::core::matches!(self, #enum_name::#var_name(_))
+                }
+
+                #[doc = #as_doc]
+                #separator
+                #(#docs)*
+                #deprecated
+                #vis fn #as_name(&self) -> Option<&#wrapped> {
+                    match self {
+                        #enum_name::#var_name(inner) => Some(inner),
+                        _ => None,
+                    }
+                }
+
+                #[doc = #try_into_doc]
+                #separator
+                #(#docs)*
+                #deprecated
+                #vis fn #try_into_name(self) -> Result<#wrapped, Self> {
+                    match self {
+                        #enum_name::#var_name(inner) => Ok(inner),
+                        other => Err(other),
+                    }
+                }
+            }
+        });
+
+        quote! {
+            #[allow(deprecated)]
+            impl #enum_name {
+                #(#accessors)*
+            }
+        }
+    }
+
+    /// Associated functions named by the plural variant, like `integers` for `Integer`, keeping
+    /// only the payloads of that variant from an iterator of values
+    fn write_extractors(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let extractors = self.enum_data.variants.iter().map(|var| {
+            let var_name = &var.ident;
+            let wrapped = get_wrapped_unnamed("EnumIntoWrapped", enum_name, var.fields.clone());
+            // variant `A` gives `as`, written as raw identifier
+            let name = plural(&to_snake_case(&var_name.to_string()));
+            let name = syn::parse_str::<Ident>(&name)
+                .unwrap_or_else(|_| Ident::new_raw(&name, Span::call_site()));
+            let doc = format!(
+                "Keeps the inner values of `{}::{}` from the values",
+                enum_name, var_name
+            );
+            let deprecated = get_deprecated(&var.attrs);
+
+            quote! {
+                #[doc = #doc]
+                #deprecated
+                #vis fn #name<I>(iter: I) -> impl Iterator<Item = #wrapped>
+                where
+                    I: IntoIterator<Item = Self>,
+                {
+                    iter.into_iter().filter_map(|value| match value {
+                        #enum_name::#var_name(inner) => Some(inner),
+                        #[allow(unreachable_patterns)]
+                        _ => None,
+                    })
+                }
+            }
+        });
+
+        quote! {
+            #[allow(deprecated)]
+            impl #enum_name {
+                #(#extractors)*
+            }
+        }
+    }
+
+    /// Exhaustive match taking a closure per variant, adding a variant breaks every call site
+    fn write_fold(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let (params, arms): (Vec<_>, Vec<_>) = self
+            .enum_data
+            .variants
+            .iter()
+            .map(|var| {
+                let var_name = &var.ident;
+                let wrapped = get_wrapped_unnamed("EnumIntoWrapped", enum_name, var.fields.clone());
+                let param = format_ident!("on_{}", to_snake_case(&var_name.to_string()));
+
+                (
+                    quote! { #param: impl FnOnce(#wrapped) -> R },
+                    quote! { #enum_name::#var_name(inner) => #param(inner) },
+                )
+            })
+            .unzip();
+
+        quote! {
+            #[allow(deprecated)]
+            impl #enum_name {
+                /// Call the closure matching the variant with its inner value
+                #[allow(clippy::too_many_arguments)]
+                #vis fn fold<R>(self, #(#params),*) -> R {
+                    match self {
+                        #(#arms,)*
+                    }
+                }
+            }
+        }
+    }
+
+    fn variant_error_name(&self) -> Ident {
+        self.error_name.clone()
+    }
+
+    fn write_variant_error(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let error_name = self.variant_error_name();
+        let enum_str = enum_name.to_string();
+        let doc = format!(
+            "Error returned when `{}` is not the variant to convert from",
+            enum_name
+        );
+
+        let found = self.enum_data.variants.iter().map(|var| {
+            let var_name = &var.ident;
+            let name = var_name.to_string();
+            quote! { #enum_name::#var_name { .. } => #name }
+        });
+        // `?` keeps working in code still returning `Result<_, ()>`, and the inherent `try_into`
+        // takes precedence over `TryInto::try_into`, so `x.try_into()` still yields `()` errors
+        let note = format!(
+            "the error of converting {} is now {}, use TryFrom or TryInto::try_into",
+            enum_name, error_name
+        );
+        let compat_unit_error = self.compat_unit_error.then(|| {
+            quote! {
+                impl ::core::convert::From<#error_name> for () {
+                    fn from(_: #error_name) -> Self {}
+                }
+
+                #[allow(deprecated)]
+                impl #enum_name {
+                    /// Converts into the payload with the former `()` error
+                    #[deprecated(note = #note)]
+                    #vis fn try_into<T>(self) -> ::core::result::Result<T, ()>
+                    where
+                        T: ::core::convert::TryFrom<Self>,
+                    {
+                        T::try_from(self).map_err(|_| ())
+                    }
+                }
+            }
+        });
+
+        quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #vis struct #error_name {
+                /// Name of the variant to convert from
+                pub expected: &'static str,
+                /// Name of the actual variant
+                pub found: &'static str,
+            }
+
+            #[allow(deprecated)]
+            impl #error_name {
+                fn new(expected: &'static str, value: &#enum_name) -> Self {
+                    let found = match value {
+                        #(#found,)*
+                    };
+                    #error_name { expected, found }
+                }
+            }
+
+            impl ::core::fmt::Display for #error_name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::write!(
+                        f,
+                        "expected variant {}::{}, found {}::{}",
+                        #enum_str,
+                        self.expected,
+                        #enum_str,
+                        self.found
+                    )
+                }
+            }
+
+            impl ::core::error::Error for #error_name {}
+
+            #compat_unit_error
+        }
+    }
+}
+
+/// English plural of a snake case name, good enough for variant names
+fn plural(name: &str) -> String {
+    let consonant_y =
+        name.ends_with('y') && !name[..name.len() - 1].ends_with(['a', 'e', 'i', 'o', 'u']);
+    if consonant_y {
+        format!("{}ies", &name[..name.len() - 1])
+    } else if ["s", "x", "z", "ch", "sh"]
+        .iter()
+        .any(|end| name.ends_with(end))
+    {
+        format!("{}es", name)
+    } else {
+        format!("{}s", name)
+    }
+}
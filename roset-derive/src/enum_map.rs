@@ -0,0 +1,156 @@
+use crate::options::RosetOptions;
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::Attribute;
+use syn::DataEnum;
+use syn::Error;
+use syn::Fields;
+use syn::Ident;
+use syn::Result;
+use syn::Visibility;
+
+pub(crate) struct EnumMap {
+    vis: Visibility,
+    enum_name: Ident,
+    enum_attrs: Vec<Attribute>,
+    enum_data: DataEnum,
+
+    map_name: Ident,
+}
+
+impl EnumMap {
+    pub fn new(
+        vis: Visibility,
+        enum_name: Ident,
+        enum_attrs: Vec<Attribute>,
+        enum_data: DataEnum,
+    ) -> Self {
+        EnumMap {
+            map_name: format_ident!("{}Map", enum_name),
+            vis,
+            enum_name,
+            enum_attrs,
+            enum_data,
+        }
+    }
+
+    pub fn parse_attributes(&mut self) -> Result<()> {
+        if self.enum_data.variants.is_empty() {
+            let reason = format!("{}: can not use EnumMap with empty enum", self.enum_name);
+            return Err(Error::new(self.enum_name.span(), reason));
+        }
+        for var in self.enum_data.variants.iter() {
+            if !matches!(var.fields, Fields::Unit) {
+                let reason = format!(
+                    "{}: can not use EnumMap with non-unit variant {}",
+                    self.enum_name, var.ident
+                );
+                return Err(Error::new(var.ident.span(), reason));
+            }
+        }
+        self.map_name =
+            RosetOptions::parse(&self.enum_attrs)?.name("map_name", self.map_name.clone());
+        Ok(())
+    }
+
+    pub fn write_output(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let map_name = &self.map_name;
+        let doc = format!(
+            "Map keyed by `{}` variants, stored in variant order",
+            enum_name
+        );
+
+        let variants: Vec<_> = self
+            .enum_data
+            .variants
+            .iter()
+            .map(|var| &var.ident)
+            .collect();
+
+        let len = variants.len();
+        let index = variants.iter().enumerate().map(|(i, var)| {
+            quote! { #enum_name::#var => #i }
+        });
+        let key = variants.iter().enumerate().map(|(i, var)| {
+            quote! { #i => #enum_name::#var }
+        });
+
+        quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            #vis struct #map_name<V> {
+                values: [Option<V>; #len],
+            }
+
+            #[allow(deprecated)]
+            impl<V> #map_name<V> {
+                fn index(key: &#enum_name) -> usize {
+                    match key {
+                        #(#index,)*
+                    }
+                }
+
+                fn key(index: usize) -> #enum_name {
+                    match index {
+                        #(#key,)*
+                        _ => ::core::unreachable!(),
+                    }
+                }
+
+                /// Create an empty map
+                #vis fn new() -> Self {
+                    #map_name {
+                        values: [(); #len].map(|_| None),
+                    }
+                }
+
+                /// Create a map with a value for every variant
+                #vis fn from_fn(mut f: impl FnMut(#enum_name) -> V) -> Self {
+                    let mut index = 0;
+                    let values = [(); #len].map(|_| {
+                        index += 1;
+                        Some(f(Self::key(index - 1)))
+                    });
+                    #map_name { values }
+                }
+
+                /// Returns a reference to the value of the variant
+                #vis fn get(&self, key: #enum_name) -> Option<&V> {
+                    self.values[Self::index(&key)].as_ref()
+                }
+
+                /// Returns a mutable reference to the value of the variant
+                #vis fn get_mut(&mut self, key: #enum_name) -> Option<&mut V> {
+                    self.values[Self::index(&key)].as_mut()
+                }
+
+                /// Insert a value for the variant, returning the old value if present
+                #vis fn insert(&mut self, key: #enum_name, value: V) -> Option<V> {
+                    self.values[Self::index(&key)].replace(value)
+                }
+
+                /// Remove the value of the variant, returning it if present
+                #vis fn remove(&mut self, key: #enum_name) -> Option<V> {
+                    self.values[Self::index(&key)].take()
+                }
+
+                /// Iterate over present values in variant order
+                #vis fn iter(&self) -> impl Iterator<Item = (#enum_name, &V)> + '_ {
+                    self.values
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(i, value)| value.as_ref().map(|value| (Self::key(i), value)))
+                }
+            }
+
+            impl<V> Default for #map_name<V> {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+        }
+    }
+}
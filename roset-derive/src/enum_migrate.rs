@@ -0,0 +1,262 @@
+use crate::attr::parse_items;
+use crate::attr::AttrItem;
+use crate::options::RosetOptions;
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::Attribute;
+use syn::DataEnum;
+use syn::Error;
+use syn::Fields;
+use syn::Ident;
+use syn::Path;
+use syn::Result;
+use syn::Variant;
+use syn::Visibility;
+
+const MIGRATE_ATTRIBUTES: &[&str] = &["from", "renamed", "removed", "added"];
+
+/// One `#[migrate(..)]`, converting from an older version of the enum
+struct Migration {
+    from: Path,
+    /// `(old, new)` variant names
+    renamed: Vec<(Ident, Ident)>,
+    /// Old variants without counterpart, converting from them fails
+    removed: Vec<Ident>,
+    /// New variants without counterpart in the old enum
+    added: Vec<Ident>,
+}
+
+pub(crate) struct EnumMigrate {
+    vis: Visibility,
+    enum_name: Ident,
+    enum_attrs: Vec<Attribute>,
+    enum_data: DataEnum,
+
+    migrations: Vec<Migration>,
+    error_name: Ident,
+}
+
+impl EnumMigrate {
+    pub fn new(
+        vis: Visibility,
+        enum_name: Ident,
+        enum_attrs: Vec<Attribute>,
+        enum_data: DataEnum,
+    ) -> Self {
+        EnumMigrate {
+            error_name: format_ident!("{}MigrateError", enum_name),
+            vis,
+            enum_name,
+            enum_attrs,
+            enum_data,
+            migrations: vec![],
+        }
+    }
+
+    /// Every `#[migrate(..)]` attribute is one source enum, the new variants are checked here and
+    /// the old ones by the compiler through the generated match
+    pub fn parse_attributes(&mut self) -> Result<()> {
+        for attr in self
+            .enum_attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("migrate"))
+        {
+            let items = parse_items("migrate", std::slice::from_ref(attr), MIGRATE_ATTRIBUTES)?;
+            let mut migration = Migration {
+                from: match items.iter().find(|item| item.is("from")) {
+                    Some(item) => item.str_value()?.parse()?,
+                    None => {
+                        let reason = "migrate attribute requires from = \"path::ToOldEnum\"";
+                        return Err(Error::new_spanned(attr, reason));
+                    }
+                },
+                renamed: vec![],
+                removed: vec![],
+                added: vec![],
+            };
+            for item in items.iter() {
+                match item.key.to_string().as_str() {
+                    "from" => {}
+                    "renamed" => {
+                        for renamed in item.list()? {
+                            let new: Ident = renamed.str_value()?.parse()?;
+                            self.check_variant(&new, renamed)?;
+                            migration.renamed.push((renamed.key.clone(), new));
+                        }
+                    }
+                    "removed" => {
+                        for removed in item.list()? {
+                            removed.flag()?;
+                            migration.removed.push(removed.key.clone());
+                        }
+                    }
+                    "added" => {
+                        for added in item.list()? {
+                            added.flag()?;
+                            self.check_variant(&added.key, added)?;
+                            migration.added.push(added.key.clone());
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            self.migrations.push(migration);
+        }
+
+        if self.migrations.is_empty() {
+            let reason = format!(
+                "{}: EnumMigrate requires #[migrate(from = \"path::ToOldEnum\")]",
+                self.enum_name
+            );
+            return Err(Error::new(self.enum_name.span(), reason));
+        }
+        let options = RosetOptions::parse(&self.enum_attrs)?;
+        self.error_name = options.name("migrate_error_name", self.error_name.clone());
+        Ok(())
+    }
+
+    fn check_variant(&self, name: &Ident, item: &AttrItem) -> Result<()> {
+        if self.variant(name).is_none() {
+            let reason = format!("{} has no variant {}", self.enum_name, name);
+            return Err(Error::new(item.span(), reason));
+        }
+        Ok(())
+    }
+
+    fn variant(&self, name: &Ident) -> Option<&Variant> {
+        self.enum_data
+            .variants
+            .iter()
+            .find(|var| var.ident.eq(name))
+    }
+
+    pub fn write_output(&self) -> TokenStream {
+        let mut output = TokenStream::new();
+        if self
+            .migrations
+            .iter()
+            .any(|migration| !migration.removed.is_empty())
+        {
+            output.extend(self.write_migrate_error());
+        }
+        for migration in self.migrations.iter() {
+            output.extend(self.write_migration(migration));
+        }
+        output
+    }
+
+    /// `From` of the old enum, or `TryFrom` failing on removed variants
+    fn write_migration(&self, migration: &Migration) -> TokenStream {
+        let enum_name = &self.enum_name;
+        let error_name = &self.error_name;
+        let from = &migration.from;
+        let from_str = quote!(#from).to_string().replace(' ', "");
+
+        // same name variants first, then the renamed ones
+        let same_name = self
+            .enum_data
+            .variants
+            .iter()
+            .map(|var| &var.ident)
+            .filter(|var| !migration.added.contains(var))
+            .filter(|var| !migration.renamed.iter().any(|(_, new)| new.eq(*var)))
+            .map(|var| (var, var));
+        let renamed = migration.renamed.iter().map(|(old, new)| (old, new));
+        let arms: Vec<_> = same_name
+            .chain(renamed)
+            .map(|(old, new)| {
+                let var = self.variant(new).unwrap();
+                match &var.fields {
+                    Fields::Unit => quote! { #from::#old => #enum_name::#new },
+                    Fields::Unnamed(fields) => {
+                        let bindings: Vec<_> = (0..fields.unnamed.len())
+                            .map(|i| format_ident!("field{}", i))
+                            .collect();
+                        quote! {
+                            #from::#old(#(#bindings),*) => #enum_name::#new(
+                                #(::core::convert::Into::into(#bindings)),*
+                            )
+                        }
+                    }
+                    Fields::Named(fields) => {
+                        let names: Vec<_> = fields.named.iter().map(|field| &field.ident).collect();
+                        quote! {
+                            #from::#old { #(#names),* } => #enum_name::#new {
+                                #(#names: ::core::convert::Into::into(#names)),*
+                            }
+                        }
+                    }
+                }
+            })
+            .collect();
+
+        if migration.removed.is_empty() {
+            return quote! {
+                #[allow(deprecated)]
+                impl ::core::convert::From<#from> for #enum_name {
+                    fn from(value: #from) -> Self {
+                        match value {
+                            #(#arms,)*
+                        }
+                    }
+                }
+            };
+        }
+
+        let removed = migration.removed.iter().map(|var| {
+            let name = var.to_string();
+            quote! {
+                #from::#var { .. } => return ::core::result::Result::Err(#error_name { from: #from_str, variant: #name })
+            }
+        });
+        quote! {
+            #[allow(deprecated)]
+            impl ::core::convert::TryFrom<#from> for #enum_name {
+                type Error = #error_name;
+
+                fn try_from(value: #from) -> ::core::result::Result<Self, Self::Error> {
+                    ::core::result::Result::Ok(match value {
+                        #(#arms,)*
+                        #(#removed,)*
+                    })
+                }
+            }
+        }
+    }
+
+    fn write_migrate_error(&self) -> TokenStream {
+        let vis = &self.vis;
+        let error_name = &self.error_name;
+        let enum_str = self.enum_name.to_string();
+        let doc = format!(
+            "Error returned when migrating from a variant removed from `{}`",
+            self.enum_name
+        );
+
+        quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #vis struct #error_name {
+                /// Path of the old enum, as written in `migrate(from)`
+                pub from: &'static str,
+                /// Name of the removed variant
+                pub variant: &'static str,
+            }
+
+            impl ::core::fmt::Display for #error_name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::write!(
+                        f,
+                        "variant {}::{} was removed from {}",
+                        self.from,
+                        self.variant,
+                        #enum_str
+                    )
+                }
+            }
+
+            impl ::core::error::Error for #error_name {}
+        }
+    }
+}
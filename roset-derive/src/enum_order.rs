@@ -0,0 +1,150 @@
+use crate::attr::is_attr;
+use crate::attr::parse_items;
+use crate::eval_discriminant;
+use proc_macro2::Literal;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Attribute;
+use syn::DataEnum;
+use syn::Error;
+use syn::Expr;
+use syn::Generics;
+use syn::Ident;
+use syn::Result;
+use syn::Visibility;
+
+pub(crate) struct EnumOrder {
+    vis: Visibility,
+    enum_name: Ident,
+    enum_attrs: Vec<Attribute>,
+    generics: Generics,
+    enum_data: DataEnum,
+
+    /// `(variant, weight)` in declaration order
+    weights: Vec<(Ident, i64)>,
+    eq: bool,
+}
+
+impl EnumOrder {
+    pub fn new(
+        vis: Visibility,
+        enum_name: Ident,
+        enum_attrs: Vec<Attribute>,
+        generics: Generics,
+        enum_data: DataEnum,
+    ) -> Self {
+        EnumOrder {
+            vis,
+            enum_name,
+            enum_attrs,
+            generics,
+            enum_data,
+            weights: vec![],
+            eq: false,
+        }
+    }
+
+    /// Weights follow the previous one like discriminants, starting from the declaration index,
+    /// without `eq` they must tell variants apart for `Ord` to agree with a structural `Eq`
+    pub fn parse_attributes(&mut self) -> Result<()> {
+        for item in parse_items("enum_order", &self.enum_attrs, &["eq"])? {
+            item.flag()?;
+            self.eq = true;
+        }
+
+        let mut next: Option<i64> = Some(0);
+        for var in self.enum_data.variants.iter() {
+            if let Some(attr) = var.attrs.iter().find(|attr| is_attr(attr, "enum_order")) {
+                next = Some(
+                    eval_discriminant(&attr.parse_args::<Expr>()?)
+                        .and_then(|weight| i64::try_from(weight).ok())
+                        .ok_or_else(|| {
+                            let reason = format!(
+                                "{}::{}: enum_order weight must be an i64 literal",
+                                self.enum_name, var.ident
+                            );
+                            Error::new_spanned(attr, reason)
+                        })?,
+                );
+            }
+            let weight = next.ok_or_else(|| {
+                let reason = format!(
+                    "{}::{}: enum_order weight overflows i64 after the previous variant",
+                    self.enum_name, var.ident
+                );
+                Error::new(var.ident.span(), reason)
+            })?;
+
+            if !self.eq {
+                if !var.fields.is_empty() {
+                    let reason = format!(
+                        "{}::{}: payload variant requires enum_order(eq) on the enum, \
+                         Ord ignores payloads so a structural PartialEq would disagree",
+                        self.enum_name, var.ident
+                    );
+                    return Err(Error::new(var.ident.span(), reason));
+                }
+                if let Some((other, _)) = self.weights.iter().find(|(_, w)| *w == weight) {
+                    let reason = format!(
+                        "{}::{}: enum_order weight {} already used by {}, \
+                         use enum_order(eq) on the enum to make them equal",
+                        self.enum_name, var.ident, weight, other
+                    );
+                    return Err(Error::new(var.ident.span(), reason));
+                }
+            }
+            self.weights.push((var.ident.clone(), weight));
+            next = weight.checked_add(1);
+        }
+        Ok(())
+    }
+
+    pub fn write_output(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+
+        let weights = self.weights.iter().map(|(var, weight)| {
+            let weight = Literal::i64_unsuffixed(*weight);
+            quote! { #enum_name::#var { .. } => #weight }
+        });
+
+        let eq = self.eq.then(|| {
+            quote! {
+                impl #impl_generics ::core::cmp::PartialEq for #enum_name #ty_generics #where_clause {
+                    fn eq(&self, other: &Self) -> bool {
+                        self.order_weight() == other.order_weight()
+                    }
+                }
+
+                impl #impl_generics ::core::cmp::Eq for #enum_name #ty_generics #where_clause {}
+            }
+        });
+
+        quote! {
+            #[allow(deprecated)]
+            impl #impl_generics #enum_name #ty_generics #where_clause {
+                /// Returns the weight the variant is ordered by
+                #vis const fn order_weight(&self) -> i64 {
+                    match *self {
+                        #(#weights,)*
+                    }
+                }
+            }
+
+            impl #impl_generics ::core::cmp::PartialOrd for #enum_name #ty_generics #where_clause {
+                fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
+                    Some(::core::cmp::Ord::cmp(self, other))
+                }
+            }
+
+            impl #impl_generics ::core::cmp::Ord for #enum_name #ty_generics #where_clause {
+                fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                    self.order_weight().cmp(&other.order_weight())
+                }
+            }
+
+            #eq
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::DataEnum;
+use syn::Generics;
+use syn::Ident;
+use syn::Visibility;
+
+pub(crate) struct EnumPayloadSize {
+    vis: Visibility,
+    enum_name: Ident,
+    generics: Generics,
+    enum_data: DataEnum,
+}
+
+impl EnumPayloadSize {
+    pub fn new(vis: Visibility, enum_name: Ident, generics: Generics, enum_data: DataEnum) -> Self {
+        EnumPayloadSize {
+            vis,
+            enum_name,
+            generics,
+            enum_data,
+        }
+    }
+
+    /// `payload_size` and `MAX_PAYLOAD_SIZE`, summing `size_of` of the fields without padding
+    pub fn write_output(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+
+        let sizes: Vec<_> = self
+            .enum_data
+            .variants
+            .iter()
+            .map(|var| {
+                let types = var.fields.iter().map(|field| &field.ty);
+                quote! { 0 #(+ ::core::mem::size_of::<#types>())* }
+            })
+            .collect();
+        let variants = self.enum_data.variants.iter().map(|var| &var.ident);
+        let count = sizes.len();
+
+        quote! {
+            #[allow(deprecated)]
+            impl #impl_generics #enum_name #ty_generics #where_clause {
+                /// Largest payload size of all variants in bytes
+                #vis const MAX_PAYLOAD_SIZE: usize = {
+                    let sizes: [usize; #count] = [#(#sizes),*];
+                    let mut max = 0;
+                    let mut i = 0;
+                    while i < sizes.len() {
+                        if sizes[i] > max {
+                            max = sizes[i];
+                        }
+                        i += 1;
+                    }
+                    max
+                };
+
+                /// Payload size of the variant in bytes, the sum of its field sizes
+                #vis const fn payload_size(&self) -> usize {
+                    match *self {
+                        #(Self::#variants { .. } => #sizes,)*
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use crate::attr::parse_items;
+use crate::eval_discriminant;
+use crate::get_discriminants;
+use crate::options::parse_int_repr;
+use crate::options::REPR_TYPES;
+use proc_macro2::Literal;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Attribute;
+use syn::DataEnum;
+use syn::Error;
+use syn::Fields;
+use syn::Ident;
+use syn::Result;
+use syn::Visibility;
+
+pub(crate) struct EnumRange {
+    vis: Visibility,
+    enum_name: Ident,
+    enum_attrs: Vec<Attribute>,
+    enum_data: DataEnum,
+
+    value_type: Option<Ident>,
+    /// `(variant, value)` sorted by value, the first variant wins on equal values
+    values: Vec<(Ident, i128)>,
+}
+
+impl EnumRange {
+    pub fn new(
+        vis: Visibility,
+        enum_name: Ident,
+        enum_attrs: Vec<Attribute>,
+        enum_data: DataEnum,
+    ) -> Self {
+        EnumRange {
+            vis,
+            enum_name,
+            enum_attrs,
+            enum_data,
+            value_type: None,
+            values: vec![],
+        }
+    }
+
+    /// Values come from `enum_value` attributes if any, from discriminants of the repr otherwise
+    pub fn parse_attributes(&mut self) -> Result<()> {
+        if self.enum_data.variants.is_empty() {
+            let reason = format!(
+                "{}: EnumRange requires at least one variant",
+                self.enum_name
+            );
+            return Err(Error::new(self.enum_name.span(), reason));
+        }
+        for var in self.enum_data.variants.iter() {
+            if !matches!(var.fields, Fields::Unit) {
+                let reason = format!("{}: EnumRange only support unit variant", self.enum_name);
+                return Err(Error::new(var.ident.span(), reason));
+            }
+        }
+
+        let variants = self.enum_data.variants.clone();
+        for var in variants.iter() {
+            let item = match parse_items("enum_value", &var.attrs, &REPR_TYPES)?.pop() {
+                Some(item) => item,
+                None => continue,
+            };
+            match &self.value_type {
+                Some(ty) if ty.ne(&item.key) => {
+                    let reason = format!("expected value type {}, found {}", ty, item.key);
+                    return Err(Error::new(item.span(), reason));
+                }
+                _ => self.value_type = Some(item.key.clone()),
+            }
+            let value = match eval_discriminant(item.expr()?) {
+                Some(value) => value,
+                None => {
+                    let reason = format!(
+                        "{}::{}: EnumRange needs an integer literal value like `-1`, \
+                         const expressions are only supported by EnumValue",
+                        self.enum_name, var.ident
+                    );
+                    return Err(Error::new_spanned(item.expr()?, reason));
+                }
+            };
+            self.values.push((var.ident.clone(), value));
+        }
+
+        if self.value_type.is_some() {
+            if self.values.len() != variants.len() {
+                let reason = format!(
+                    "{}: enum_value attribute must be used for all variants",
+                    self.enum_name
+                );
+                return Err(Error::new(self.enum_name.span(), reason));
+            }
+        } else {
+            self.value_type = parse_int_repr(&self.enum_attrs)?;
+            if self.value_type.is_none() {
+                let reason = format!(
+                    "{}: EnumRange requires enum_value attributes or an integer repr like #[repr(u8)]",
+                    self.enum_name
+                );
+                return Err(Error::new(self.enum_name.span(), reason));
+            }
+            self.values = get_discriminants(&self.enum_name, &self.enum_data);
+        }
+        // stable sort keeps declaration order of equal values
+        self.values.sort_by_key(|(_, value)| *value);
+        Ok(())
+    }
+
+    pub fn write_output(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let value_type = self.value_type.as_ref().unwrap();
+        let (last, _) = self.values.last().unwrap();
+        let min = Literal::i128_unsuffixed(self.values[0].1);
+        let max = Literal::i128_unsuffixed(self.values[self.values.len() - 1].1);
+
+        // a value up to the midpoint of two neighbours maps to the lower one
+        let clamped = self.values.windows(2).map(|pair| {
+            let ((var, low), (_, high)) = (&pair[0], &pair[1]);
+            let midpoint = Literal::i128_unsuffixed(low + (high - low) / 2);
+            quote! {
+                if value <= #midpoint {
+                    return #enum_name::#var;
+                }
+            }
+        });
+
+        quote! {
+            #[allow(deprecated)]
+            impl #enum_name {
+                /// The smallest value of the variants
+                #vis const MIN: #value_type = #min;
+
+                /// The largest value of the variants
+                #vis const MAX: #value_type = #max;
+
+                /// Whether the value lies between `MIN` and `MAX`, inclusive
+                #[allow(unused_comparisons)]
+                #vis const fn in_range(value: #value_type) -> bool {
+                    Self::MIN <= value && value <= Self::MAX
+                }
+
+                /// Returns the variant with the nearest value, the lower one on a tie
+                #vis const fn from_clamped(value: #value_type) -> Self {
+                    #(#clamped)*
+                    #enum_name::#last
+                }
+            }
+        }
+    }
+}
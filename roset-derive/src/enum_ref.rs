@@ -0,0 +1,189 @@
+use crate::attr::parse_items;
+use crate::get_deprecated;
+use crate::options::RosetOptions;
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::Attribute;
+use syn::DataEnum;
+use syn::Error;
+use syn::Fields;
+use syn::GenericArgument;
+use syn::Generics;
+use syn::Ident;
+use syn::PathArguments;
+use syn::Result;
+use syn::Type;
+use syn::Visibility;
+
+pub(crate) struct EnumRef {
+    vis: Visibility,
+    enum_name: Ident,
+    enum_attrs: Vec<Attribute>,
+    generics: Generics,
+    enum_data: DataEnum,
+
+    ref_name: Ident,
+    /// Traits derived for the borrowed enum besides `Clone` and `Copy`
+    derives: Vec<Ident>,
+}
+
+impl EnumRef {
+    pub fn new(
+        vis: Visibility,
+        enum_name: Ident,
+        enum_attrs: Vec<Attribute>,
+        generics: Generics,
+        enum_data: DataEnum,
+    ) -> Self {
+        EnumRef {
+            ref_name: format_ident!("{}Ref", enum_name),
+            vis,
+            enum_name,
+            enum_attrs,
+            generics,
+            enum_data,
+            derives: vec![],
+        }
+    }
+
+    pub fn parse_attributes(&mut self) -> Result<()> {
+        if !self.generics.params.is_empty() {
+            let reason = format!("{}: EnumRef does not support generic enums", self.enum_name);
+            return Err(Error::new(self.enum_name.span(), reason));
+        }
+        if self
+            .enum_data
+            .variants
+            .iter()
+            .all(|var| var.fields.is_empty())
+        {
+            let reason = format!(
+                "{}: EnumRef requires a variant with payload",
+                self.enum_name
+            );
+            return Err(Error::new(self.enum_name.span(), reason));
+        }
+        for item in parse_items("enum_ref", &self.enum_attrs, &["derive"])? {
+            for derive in item.list()? {
+                derive.flag()?;
+                self.derives.push(derive.key.clone());
+            }
+        }
+        self.ref_name =
+            RosetOptions::parse(&self.enum_attrs)?.name("ref_name", self.ref_name.clone());
+        Ok(())
+    }
+
+    /// `{Enum}Ref<'a>` borrowing every payload, `String` as `&str` and `Vec<T>` as `&[T]`, with
+    /// `as_ref` and `to_owned` between both
+    pub fn write_output(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let ref_name = &self.ref_name;
+        let derives = &self.derives;
+        let doc = format!(
+            "Borrowed form of `{}`, created by `{}::as_ref`",
+            enum_name, enum_name
+        );
+
+        let mut variants = vec![];
+        let mut as_ref_arms = vec![];
+        let mut to_owned_arms = vec![];
+        for var in self.enum_data.variants.iter() {
+            let var_name = &var.ident;
+            let deprecated = get_deprecated(&var.attrs);
+            let types = var.fields.iter().map(|field| borrowed_type(&field.ty));
+            match &var.fields {
+                Fields::Unit => {
+                    variants.push(quote! { #deprecated #var_name });
+                    as_ref_arms.push(quote! { #enum_name::#var_name => #ref_name::#var_name });
+                    to_owned_arms.push(quote! { #ref_name::#var_name => #enum_name::#var_name });
+                }
+                Fields::Unnamed(fields) => {
+                    let bindings: Vec<_> = (0..fields.unnamed.len())
+                        .map(|i| format_ident!("field{}", i))
+                        .collect();
+                    variants.push(quote! { #deprecated #var_name(#(#types),*) });
+                    as_ref_arms.push(quote! {
+                        #enum_name::#var_name(#(#bindings),*) => #ref_name::#var_name(#(#bindings),*)
+                    });
+                    to_owned_arms.push(quote! {
+                        #ref_name::#var_name(#(#bindings),*) => #enum_name::#var_name(
+                            #(::std::borrow::ToOwned::to_owned(#bindings)),*
+                        )
+                    });
+                }
+                Fields::Named(fields) => {
+                    let names: Vec<_> = fields.named.iter().map(|field| &field.ident).collect();
+                    variants.push(quote! { #deprecated #var_name { #(#names: #types),* } });
+                    as_ref_arms.push(quote! {
+                        #enum_name::#var_name { #(#names),* } => #ref_name::#var_name { #(#names),* }
+                    });
+                    to_owned_arms.push(quote! {
+                        #ref_name::#var_name { #(#names),* } => #enum_name::#var_name {
+                            #(#names: ::std::borrow::ToOwned::to_owned(#names)),*
+                        }
+                    });
+                }
+            }
+        }
+
+        quote! {
+            #[doc = #doc]
+            #[derive(Clone, Copy #(, #derives)*)]
+            #vis enum #ref_name<'a> {
+                #(#variants,)*
+            }
+
+            #[allow(deprecated)]
+            impl #enum_name {
+                /// Borrows the payloads, without copying them
+                #vis fn as_ref(&self) -> #ref_name<'_> {
+                    match self {
+                        #(#as_ref_arms,)*
+                    }
+                }
+            }
+
+            #[allow(deprecated)]
+            impl #ref_name<'_> {
+                /// Clones the payloads into the owned enum
+                #vis fn to_owned(&self) -> #enum_name {
+                    match *self {
+                        #(#to_owned_arms,)*
+                    }
+                }
+            }
+
+            impl<'a> ::core::convert::From<&'a #enum_name> for #ref_name<'a> {
+                fn from(value: &'a #enum_name) -> Self {
+                    value.as_ref()
+                }
+            }
+
+            impl ::core::convert::From<#ref_name<'_>> for #enum_name {
+                fn from(value: #ref_name<'_>) -> Self {
+                    value.to_owned()
+                }
+            }
+        }
+    }
+}
+
+/// `&'a str` for `String`, `&'a [T]` for `Vec<T>` and `&'a T` otherwise
+fn borrowed_type(ty: &Type) -> TokenStream {
+    if let Type::Path(path) = ty {
+        let last = path.path.segments.last().unwrap();
+        if path.qself.is_none() && last.ident == "String" {
+            return quote! { &'a str };
+        }
+        if let (true, PathArguments::AngleBracketed(args)) = (last.ident == "Vec", &last.arguments)
+        {
+            if let Some(GenericArgument::Type(inner)) = args.args.first() {
+                return quote! { &'a [#inner] };
+            }
+        }
+    }
+    quote! { &'a #ty }
+}
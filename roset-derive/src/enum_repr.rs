@@ -0,0 +1,275 @@
+use crate::attr::parse_items;
+use crate::options::parse_int_repr;
+use crate::options::RosetOptions;
+use proc_macro2::Literal;
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::Attribute;
+use syn::DataEnum;
+use syn::Error;
+use syn::Fields;
+use syn::Ident;
+use syn::Result;
+use syn::Visibility;
+
+pub(crate) struct EnumRepr {
+    vis: Visibility,
+    enum_name: Ident,
+    enum_attrs: Vec<Attribute>,
+    enum_data: DataEnum,
+
+    repr: Option<Ident>,
+    bytemuck: bool,
+    /// Valid discriminants as sorted `(start, end)` ranges with `deny_unknown_variants`
+    ranges: Option<Vec<(i128, i128)>>,
+    error_name: Ident,
+}
+
+impl EnumRepr {
+    pub fn new(
+        vis: Visibility,
+        enum_name: Ident,
+        enum_attrs: Vec<Attribute>,
+        enum_data: DataEnum,
+    ) -> Self {
+        EnumRepr {
+            error_name: format_ident!("{}ReprError", enum_name),
+            vis,
+            enum_name,
+            enum_attrs,
+            enum_data,
+            repr: None,
+            bytemuck: false,
+            ranges: None,
+        }
+    }
+
+    pub fn parse_attributes(&mut self) -> Result<()> {
+        self.error_name =
+            RosetOptions::parse(&self.enum_attrs)?.name("repr_error_name", self.error_name.clone());
+        self.repr = parse_int_repr(&self.enum_attrs)?;
+        if self.repr.is_none() {
+            let reason = format!(
+                "{}: EnumRepr requires an integer repr like #[repr(u8)]",
+                self.enum_name
+            );
+            return Err(Error::new(self.enum_name.span(), reason));
+        }
+
+        for var in self.enum_data.variants.iter() {
+            if !matches!(var.fields, Fields::Unit) {
+                let reason = format!("{}: EnumRepr only support unit variant", self.enum_name);
+                return Err(Error::new(var.ident.span(), reason));
+            }
+        }
+
+        let supported = ["bytemuck", "deny_unknown_variants"];
+        for item in parse_items("enum_repr", &self.enum_attrs, &supported)? {
+            item.flag()?;
+            if item.is("deny_unknown_variants") {
+                self.ranges = Some(self.parse_ranges()?);
+                continue;
+            }
+            if !cfg!(feature = "bytemuck") {
+                let reason = "enum_repr(bytemuck) requires the `bytemuck` feature of roset";
+                return Err(Error::new(item.span(), reason));
+            }
+            self.bytemuck = true;
+        }
+        Ok(())
+    }
+
+    /// Ranges of consecutive discriminants, which must be literals to be known here
+    fn parse_ranges(&self) -> Result<Vec<(i128, i128)>> {
+        let mut next = 0;
+        let mut values = vec![];
+        for var in self.enum_data.variants.iter() {
+            if let Some((_, expr)) = &var.discriminant {
+                next = crate::eval_discriminant(expr).ok_or_else(|| {
+                    let reason = format!(
+                        "{}::{}: deny_unknown_variants requires integer literal discriminants",
+                        self.enum_name, var.ident
+                    );
+                    Error::new(var.ident.span(), reason)
+                })?;
+            }
+            values.push(next);
+            next += 1;
+        }
+        values.sort_unstable();
+
+        let mut ranges: Vec<(i128, i128)> = vec![];
+        for value in values {
+            match ranges.last_mut() {
+                Some((_, end)) if *end + 1 == value => *end = value,
+                _ => ranges.push((value, value)),
+            }
+        }
+        Ok(ranges)
+    }
+
+    fn repr_error_name(&self) -> Ident {
+        self.error_name.clone()
+    }
+
+    pub fn write_output(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let repr = self.repr.as_ref().unwrap();
+        let error_name = self.repr_error_name();
+        let error_doc = format!(
+            "Error returned when a value is not a discriminant of `{}`",
+            enum_name
+        );
+        let error_display = match &self.ranges {
+            Some(ranges) => {
+                let expected: Vec<_> = ranges
+                    .iter()
+                    .map(|(start, end)| match start == end {
+                        true => start.to_string(),
+                        false => format!("{}..={}", start, end),
+                    })
+                    .collect();
+                format!(
+                    "value {{}} does not match any variant of {}, expected {}",
+                    enum_name,
+                    expected.join(", ")
+                )
+            }
+            None => format!("value {{}} does not match any variant of {}", enum_name),
+        };
+
+        let variants: Vec<_> = self
+            .enum_data
+            .variants
+            .iter()
+            .map(|var| &var.ident)
+            .collect();
+        // discriminants are read back by casting, so any const expression is supported
+        let names: Vec<_> = variants
+            .iter()
+            .map(|var| format_ident!("REPR_{}", var))
+            .collect();
+        let consts = variants.iter().zip(names.iter()).map(|(var, name)| {
+            quote! { const #name: #repr = #enum_name::#var as #repr; }
+        });
+
+        let bytemuck = self.bytemuck.then(|| {
+            quote! {
+                // SAFETY: fieldless enum with an integer repr, valid bit patterns are checked
+                unsafe impl ::bytemuck::CheckedBitPattern for #enum_name {
+                    type Bits = #repr;
+
+                    fn is_valid_bit_pattern(bits: &#repr) -> bool {
+                        Self::from_repr(*bits).is_some()
+                    }
+                }
+
+                // SAFETY: fieldless enum with an integer repr has no padding
+                unsafe impl ::bytemuck::NoUninit for #enum_name {}
+            }
+        });
+
+        let (from_repr, checked) = match &self.ranges {
+            Some(ranges) => {
+                let patterns: Vec<_> = ranges
+                    .iter()
+                    .map(|(start, end)| {
+                        let (start, end) = (
+                            Literal::i128_unsuffixed(*start),
+                            Literal::i128_unsuffixed(*end),
+                        );
+                        quote! { #start..=#end }
+                    })
+                    .collect();
+                let from_repr = quote! {
+                    match value {
+                        // SAFETY: the ranges cover exactly the discriminants
+                        #(#patterns)|* => Some(unsafe { Self::from_repr_unchecked(value) }),
+                        _ => None,
+                    }
+                };
+                let checked = quote! {
+                    /// Valid discriminants as inclusive ranges, in ascending order
+                    #vis const REPR_RANGES: &'static [::core::ops::RangeInclusive<#repr>] =
+                        &[#(#patterns),*];
+
+                    /// Returns the variant with the discriminant without checking it
+                    ///
+                    /// # Safety
+                    ///
+                    /// `value` must be the discriminant of a variant, see `REPR_RANGES`
+                    #vis const unsafe fn from_repr_unchecked(value: #repr) -> Self {
+                        // SAFETY: the caller checked the discriminant, the enum has the repr layout
+                        unsafe { ::core::mem::transmute::<#repr, Self>(value) }
+                    }
+                };
+                (from_repr, Some(checked))
+            }
+            None => (
+                quote! {
+                    #(#consts)*
+                    match value {
+                        #(#names => Some(#enum_name::#variants),)*
+                        _ => None,
+                    }
+                },
+                None,
+            ),
+        };
+
+        quote! {
+            #[doc = #error_doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #vis struct #error_name {
+                /// The rejected value
+                pub value: #repr,
+            }
+
+            impl ::core::fmt::Display for #error_name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::write!(f, #error_display, self.value)
+                }
+            }
+
+            impl ::core::error::Error for #error_name {}
+
+            #[allow(deprecated)]
+            impl #enum_name {
+                /// Returns the discriminant of the variant
+                #vis const fn to_repr(&self) -> #repr {
+                    match self {
+                        #(#enum_name::#variants => #enum_name::#variants as #repr,)*
+                    }
+                }
+
+                /// Returns the variant with the discriminant, if any
+                #[allow(non_upper_case_globals)]
+                #vis const fn from_repr(value: #repr) -> Option<Self> {
+                    #from_repr
+                }
+
+                #checked
+            }
+
+            #[allow(deprecated)]
+            impl ::core::convert::TryFrom<#repr> for #enum_name {
+                type Error = #error_name;
+
+                fn try_from(value: #repr) -> ::core::result::Result<Self, Self::Error> {
+                    Self::from_repr(value).ok_or(#error_name { value })
+                }
+            }
+
+            #[allow(deprecated)]
+            impl ::core::convert::From<#enum_name> for #repr {
+                fn from(value: #enum_name) -> Self {
+                    value.to_repr()
+                }
+            }
+
+            #bytemuck
+        }
+    }
+}
@@ -0,0 +1,329 @@
+use crate::attr::is_attr;
+use crate::attr::parse_items;
+use crate::const_assert_unique;
+use crate::eval_discriminant;
+use crate::get_deprecated;
+use crate::get_doc_attrs;
+use crate::options::RosetOptions;
+use crate::warning;
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::Attribute;
+use syn::DataEnum;
+use syn::Error;
+use syn::Expr;
+use syn::ExprLit;
+use syn::Ident;
+use syn::Lit;
+use syn::Result;
+use syn::Visibility;
+
+const ENUM_ATTRIBUTES: &[&str] = &["contiguous", "reserved_tags", "deny_reserved"];
+
+pub(crate) struct EnumTag {
+    vis: Visibility,
+    enum_name: Ident,
+    enum_attrs: Vec<Attribute>,
+    enum_data: DataEnum,
+
+    /// Tags of every variant, integer literals or const expressions
+    tag_exprs: Vec<(Ident, Expr)>,
+    /// Tags given by literals, checked by the macro, the others are checked at compile time
+    tags: Vec<(Ident, u16)>,
+    contiguous: bool,
+    deny_reserved: bool,
+    /// Tags of removed variants, which must not be given out again
+    reserved: Vec<u16>,
+    /// Variants reusing a reserved tag, warned about unless denied
+    reused: Vec<(Ident, u16)>,
+    kind_name: Ident,
+}
+
+impl EnumTag {
+    pub fn new(
+        vis: Visibility,
+        enum_name: Ident,
+        enum_attrs: Vec<Attribute>,
+        enum_data: DataEnum,
+    ) -> Self {
+        EnumTag {
+            kind_name: format_ident!("{}Kind", enum_name),
+            vis,
+            enum_name,
+            enum_attrs,
+            enum_data,
+            tag_exprs: vec![],
+            tags: vec![],
+            contiguous: false,
+            deny_reserved: false,
+            reserved: vec![],
+            reused: vec![],
+        }
+    }
+
+    pub fn parse_attributes(&mut self) -> Result<()> {
+        self.kind_name =
+            RosetOptions::parse(&self.enum_attrs)?.name("kind_name", self.kind_name.clone());
+        for item in parse_items("enum_tag", &self.enum_attrs, ENUM_ATTRIBUTES)? {
+            match item.key.to_string().as_str() {
+                "contiguous" => item.flag().map(|_| self.contiguous = true)?,
+                "deny_reserved" => item.flag().map(|_| self.deny_reserved = true)?,
+                "reserved_tags" => self.reserved.extend(parse_tag_list(item.expr()?)?),
+                _ => unreachable!(),
+            }
+        }
+        self.parse_variant_tags()?;
+
+        for (var_name, tag) in self.tags.iter() {
+            if !self.reserved.contains(tag) {
+                continue;
+            }
+            if self.deny_reserved {
+                let reason = format!("{}::{}: tag {} is reserved", self.enum_name, var_name, tag);
+                return Err(Error::new(var_name.span(), reason));
+            }
+            self.reused.push((var_name.clone(), *tag));
+        }
+
+        if self.contiguous && !self.has_tag_consts() {
+            // reserved tags fill the gaps they left
+            let mut used: Vec<_> = self.tags.iter().map(|(_, tag)| *tag).collect();
+            used.extend(self.reserved.iter().copied());
+            used.sort_unstable();
+            used.dedup();
+            if let Some(pair) = used.windows(2).find(|pair| pair[1] - pair[0] > 1) {
+                let reason = format!(
+                    "{}: contiguous tags expected, {} is missing",
+                    self.enum_name,
+                    pair[0] + 1
+                );
+                return Err(Error::new(self.enum_name.span(), reason));
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_variant_tags(&mut self) -> Result<()> {
+        for var in self.enum_data.variants.iter() {
+            let title = format!("{}::{}", self.enum_name, var.ident);
+            let attr = match var.attrs.iter().find(|attr| is_attr(attr, "enum_tag")) {
+                Some(attr) => attr,
+                None => {
+                    let reason = format!("{}: enum_tag(..) must be used for all variants", title);
+                    return Err(Error::new(var.ident.span(), reason));
+                }
+            };
+
+            let expr: Expr = attr.parse_args()?;
+            self.tag_exprs.push((var.ident.clone(), expr.clone()));
+            let tag = match eval_discriminant(&expr) {
+                Some(tag) => u16::try_from(tag).map_err(|_| {
+                    let reason = format!("{}: tag {} does not fit in u16", title, tag);
+                    Error::new_spanned(attr, reason)
+                })?,
+                None => continue,
+            };
+            if let Some((other, _)) = self.tags.iter().find(|(_, other)| *other == tag) {
+                let reason = format!("{}: tag {} already used by {}", title, tag, other);
+                return Err(Error::new_spanned(attr, reason));
+            }
+            self.tags.push((var.ident.clone(), tag));
+        }
+        Ok(())
+    }
+
+    /// Whether some tags are const expressions, only known at compile time
+    fn has_tag_consts(&self) -> bool {
+        self.tags.len() < self.tag_exprs.len()
+    }
+
+    /// Uniqueness, `deny_reserved` and `contiguous` checks of tags given by const expressions
+    fn write_tag_asserts(&self) -> TokenStream {
+        let title = format!("EnumTag for {}", self.enum_name);
+        let values: Vec<_> = self.tag_exprs.iter().map(|(var, tag)| (var, tag)).collect();
+        let mut asserts = const_assert_unique(&title, &quote! { u16 }, &values, false);
+
+        let count = self.tag_exprs.len();
+        let tags: Vec<_> = self.tag_exprs.iter().map(|(_, tag)| tag).collect();
+        let reserved = &self.reserved;
+        let reserved_count = reserved.len();
+        if self.deny_reserved && !reserved.is_empty() {
+            let messages = self
+                .tag_exprs
+                .iter()
+                .map(|(var, _)| format!("{}: tag of {} is reserved", title, var));
+            asserts.extend(quote! {
+                const _: () = {
+                    const TAGS: [u16; #count] = [#(#tags),*];
+                    const RESERVED: [u16; #reserved_count] = [#(#reserved),*];
+                    const MESSAGES: [&str; #count] = [#(#messages),*];
+                    let mut i = 0;
+                    while i < #count {
+                        let mut j = 0;
+                        while j < #reserved_count {
+                            if TAGS[i] == RESERVED[j] {
+                                panic!("{}", MESSAGES[i]);
+                            }
+                            j += 1;
+                        }
+                        i += 1;
+                    }
+                };
+            });
+        }
+        if self.contiguous {
+            // reserved tags fill the gaps they left
+            let message = format!("{}: contiguous tags expected", title);
+            let len = count + reserved_count;
+            asserts.extend(quote! {
+                const _: () = {
+                    const TAGS: [u16; #len] = [#(#tags,)* #(#reserved,)*];
+                    let (mut min, mut max, mut i) = (u16::MAX, 0, 0);
+                    while i < #len {
+                        if TAGS[i] < min {
+                            min = TAGS[i];
+                        }
+                        if TAGS[i] > max {
+                            max = TAGS[i];
+                        }
+                        i += 1;
+                    }
+                    let mut tag = min;
+                    while tag < max {
+                        tag += 1;
+                        let (mut found, mut j) = (false, 0);
+                        while j < #len {
+                            found |= TAGS[j] == tag;
+                            j += 1;
+                        }
+                        if !found {
+                            panic!(#message);
+                        }
+                    }
+                };
+            });
+        }
+        asserts
+    }
+
+    pub fn write_output(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let kind_name = &self.kind_name;
+        let doc = format!(
+            "Fieldless kind of `{}` variants, identified by wire tags",
+            enum_name
+        );
+
+        let kinds = self.enum_data.variants.iter().map(|var| {
+            let var_name = &var.ident;
+            let docs = get_doc_attrs(&var.attrs);
+            let deprecated = get_deprecated(&var.attrs);
+            quote! {
+                #(#docs)*
+                #deprecated
+                #var_name
+            }
+        });
+        let variants: Vec<_> = self.tag_exprs.iter().map(|(var, _)| var).collect();
+        let tags: Vec<_> = self.tag_exprs.iter().map(|(_, tag)| tag).collect();
+        // const expressions can not be patterns, they are compared one by one
+        let from_tag = match self.has_tag_consts() {
+            false => quote! {
+                match tag {
+                    #(#tags => Some(&#kind_name::#variants),)*
+                    _ => None,
+                }
+            },
+            true => quote! {
+                #(
+                    if tag == #tags {
+                        return Some(&#kind_name::#variants);
+                    }
+                )*
+                None
+            },
+        };
+        let asserts = self.has_tag_consts().then(|| self.write_tag_asserts());
+        let reused = self.reused.iter().map(|(var_name, tag)| {
+            let note = format!(
+                "{}::{} reuses reserved tag {}, deny with enum_tag(deny_reserved)",
+                enum_name, var_name, tag
+            );
+            warning(var_name.span(), "RESERVED_TAG_REUSED", &note)
+        });
+
+        quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #vis enum #kind_name {
+                #(#kinds,)*
+            }
+
+            #[allow(deprecated)]
+            impl #kind_name {
+                /// Returns the wire tag of the kind
+                #vis const fn tag(&self) -> u16 {
+                    match self {
+                        #(#kind_name::#variants => #tags,)*
+                    }
+                }
+
+                /// Returns the kind with the wire tag, if any
+                #vis const fn from_tag(tag: u16) -> Option<&'static #kind_name> {
+                    #from_tag
+                }
+            }
+
+            #[allow(deprecated)]
+            impl #enum_name {
+                /// Returns the wire tag of the variant, independent of declaration order
+                #vis const fn tag(&self) -> u16 {
+                    match self {
+                        #(#enum_name::#variants { .. } => #tags,)*
+                    }
+                }
+
+                /// Returns the fieldless kind of the variant
+                #vis const fn kind(&self) -> #kind_name {
+                    match self {
+                        #(#enum_name::#variants { .. } => #kind_name::#variants,)*
+                    }
+                }
+
+                /// Returns the kind with the wire tag, if any
+                #vis const fn from_tag(tag: u16) -> Option<&'static #kind_name> {
+                    #kind_name::from_tag(tag)
+                }
+            }
+
+            #(#reused)*
+
+            #asserts
+        }
+    }
+}
+
+/// Integers of an array like `[3, 7]`
+fn parse_tag_list(expr: &Expr) -> Result<Vec<u16>> {
+    let array = match expr {
+        Expr::Array(array) => array,
+        _ => {
+            return Err(Error::new_spanned(
+                expr,
+                "expected a list of tags like [3, 7]",
+            ))
+        }
+    };
+    array
+        .elems
+        .iter()
+        .map(|elem| match elem {
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(lit), ..
+            }) => lit.base10_parse(),
+            _ => Err(Error::new_spanned(elem, "expected an integer tag")),
+        })
+        .collect()
+}
@@ -0,0 +1,167 @@
+use crate::get_deprecated;
+use crate::to_snake_case;
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::DataEnum;
+use syn::Fields;
+use syn::Generics;
+use syn::Ident;
+use syn::Visibility;
+
+pub(crate) struct EnumUnwrap {
+    vis: Visibility,
+    enum_name: Ident,
+    generics: Generics,
+    enum_data: DataEnum,
+}
+
+impl EnumUnwrap {
+    pub fn new(vis: Visibility, enum_name: Ident, generics: Generics, enum_data: DataEnum) -> Self {
+        EnumUnwrap {
+            vis,
+            enum_name,
+            generics,
+            enum_data,
+        }
+    }
+
+    /// `unwrap_*` per tuple variant, panicking with both variant names on mismatch, and `map_*`
+    /// transforming the payload in place
+    pub fn write_output(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+
+        let names: Vec<_> = self
+            .enum_data
+            .variants
+            .iter()
+            .map(|var| {
+                let var_name = &var.ident;
+                let name = format!("{}::{}", enum_name, var_name);
+                quote! { #enum_name::#var_name { .. } => #name }
+            })
+            .collect();
+
+        let unwraps = self.enum_data.variants.iter().filter_map(|var| {
+            let fields = match &var.fields {
+                Fields::Unnamed(fields) => &fields.unnamed,
+                _ => return None,
+            };
+
+            let var_name = &var.ident;
+            let unwrap_name = format_ident!("unwrap_{}", to_snake_case(&var_name.to_string()));
+            let doc = format!(
+                "Returns the inner value of `{}::{}`, panics for other variants",
+                enum_name, var_name
+            );
+            let message = format!(
+                "called `{}::{}()` on a `{{}}` value",
+                enum_name, unwrap_name
+            );
+
+            let types = fields.iter().map(|field| &field.ty);
+            let bindings: Vec<_> = (0..fields.len())
+                .map(|i| format_ident!("field{}", i))
+                .collect();
+            let (output, value) = match fields.len() {
+                1 => (quote! { #(#types)* }, quote! { #(#bindings)* }),
+                _ => (quote! { (#(#types,)*) }, quote! { (#(#bindings,)*) }),
+            };
+            let deprecated = get_deprecated(&var.attrs);
+            let map_name = format_ident!("map_{}", to_snake_case(&var_name.to_string()));
+            let map_doc = format!(
+                "Maps the inner value of `{}::{}`, other variants are returned unchanged",
+                enum_name, var_name
+            );
+            let mapped = match fields.len() {
+                1 => quote! { #enum_name::#var_name(f(#(#bindings)*)) },
+                _ => quote! {
+                    {
+                        let (#(#bindings,)*) = f((#(#bindings,)*));
+                        #enum_name::#var_name(#(#bindings),*)
+                    }
+                },
+            };
+
+            Some(quote! {
+                #[doc = #map_doc]
+                #deprecated
+                #vis fn #map_name(self, f: impl ::core::ops::FnOnce(#output) -> #output) -> Self {
+                    match self {
+                        #enum_name::#var_name(#(#bindings),*) => #mapped,
+                        other => other,
+                    }
+                }
+
+                #[doc = #doc]
+                #deprecated
+                #[track_caller]
+                #vis fn #unwrap_name(self) -> #output {
+                    match self {
+                        #enum_name::#var_name(#(#bindings),*) => #value,
+                        other => {
+                            let found = match other {
+                                #(#names,)*
+                            };
+                            ::core::panic!(#message, found)
+                        }
+                    }
+                }
+            })
+        });
+
+        let map_payloads = self.write_map_payloads();
+
+        quote! {
+            #[allow(deprecated)]
+            impl #impl_generics #enum_name #ty_generics #where_clause {
+                #(#unwraps)*
+                #map_payloads
+            }
+        }
+    }
+
+    /// `map_payloads` when every variant is a unit or a tuple of one field of the same type
+    fn write_map_payloads(&self) -> Option<TokenStream> {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let mut payload_type = None;
+        for var in self.enum_data.variants.iter() {
+            match &var.fields {
+                Fields::Unit => {}
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    // types are compared as written, an alias is a different type here
+                    let ty = &fields.unnamed[0].ty;
+                    let written = quote!(#ty).to_string();
+                    match &payload_type {
+                        Some((_, other)) if *other != written => return None,
+                        _ => payload_type = Some((ty, written)),
+                    }
+                }
+                _ => return None,
+            }
+        }
+        let (payload_type, _) = payload_type?;
+
+        let arms = self.enum_data.variants.iter().map(|var| {
+            let var_name = &var.ident;
+            match &var.fields {
+                Fields::Unit => quote! { #enum_name::#var_name => #enum_name::#var_name },
+                _ => quote! { #enum_name::#var_name(field0) => #enum_name::#var_name(f(field0)) },
+            }
+        });
+        Some(quote! {
+            /// Maps the payload of any variant, unit variants are returned unchanged
+            #vis fn map_payloads(
+                self,
+                f: impl ::core::ops::FnOnce(#payload_type) -> #payload_type,
+            ) -> Self {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        })
+    }
+}
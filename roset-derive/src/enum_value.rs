@@ -0,0 +1,225 @@
+use crate::attr::parse_items;
+use crate::const_assert_unique;
+use crate::eval_discriminant;
+use crate::options::REPR_TYPES;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::DataEnum;
+use syn::Error;
+use syn::Expr;
+use syn::Fields;
+use syn::Ident;
+use syn::Result;
+use syn::Visibility;
+
+pub(crate) struct EnumValue {
+    vis: Visibility,
+    enum_name: Ident,
+    enum_data: DataEnum,
+
+    value_type: Option<Ident>,
+    /// Integer literals or const expressions, the latter are checked at compile time
+    values: Vec<(Ident, Expr)>,
+}
+
+impl EnumValue {
+    pub fn new(vis: Visibility, enum_name: Ident, enum_data: DataEnum) -> Self {
+        EnumValue {
+            vis,
+            enum_name,
+            enum_data,
+            value_type: None,
+            values: vec![],
+        }
+    }
+
+    pub fn parse_attributes(&mut self) -> Result<()> {
+        let variants = self.enum_data.variants.clone();
+        for var in variants.iter() {
+            let title = format!(
+                "enum_value attributes for {}::{} parse error",
+                self.enum_name, var.ident
+            );
+
+            if !matches!(var.fields, Fields::Unit) {
+                let reason = format!("{}: EnumValue only support unit variant", title);
+                return Err(Error::new(var.ident.span(), reason));
+            }
+
+            let item = match parse_items("enum_value", &var.attrs, &REPR_TYPES)?.pop() {
+                Some(item) => item,
+                None => {
+                    let reason = format!(
+                        "{}: enum_value attribute must be used for all variants",
+                        title
+                    );
+                    return Err(Error::new(var.ident.span(), reason));
+                }
+            };
+
+            let value_type = item.key.clone();
+            match &self.value_type {
+                Some(ty) if ty.ne(&value_type) => {
+                    let reason = format!("expected value type {}, found {}", ty, value_type);
+                    return Err(Error::new(item.span(), reason));
+                }
+                _ => self.value_type = Some(value_type),
+            }
+
+            let value = item.expr()?.clone();
+            if let Some(literal) = eval_discriminant(&value) {
+                let used = self
+                    .values
+                    .iter()
+                    .find(|(_, other)| eval_discriminant(other) == Some(literal));
+                if let Some((other, _)) = used {
+                    let reason = format!("value {} already used by {}", literal, other);
+                    return Err(Error::new_spanned(value, reason));
+                }
+            }
+            self.values.push((var.ident.clone(), value));
+        }
+        Ok(())
+    }
+
+    pub fn write_output(&self) -> TokenStream {
+        let vis = &self.vis;
+        let enum_name = &self.enum_name;
+        let value_type = match &self.value_type {
+            Some(ty) => ty,
+            None => return TokenStream::new(),
+        };
+
+        let to_value = self.values.iter().map(|(var, value)| {
+            quote! { #enum_name::#var => #value }
+        });
+        // const expressions can not be patterns, they are compared one by one
+        let literal = self
+            .values
+            .iter()
+            .all(|(_, value)| eval_discriminant(value).is_some());
+        let from_value = match literal {
+            true => {
+                let arms = self.values.iter().map(|(var, value)| {
+                    quote! { #value => Some(#enum_name::#var) }
+                });
+                quote! {
+                    match value {
+                        #(#arms,)*
+                        _ => None,
+                    }
+                }
+            }
+            false => {
+                let checks = self.values.iter().map(|(var, value)| {
+                    quote! {
+                        if value == #value {
+                            return Some(#enum_name::#var);
+                        }
+                    }
+                });
+                quote! {
+                    #(#checks)*
+                    None
+                }
+            }
+        };
+        let unique = (!literal).then(|| {
+            let title = format!("EnumValue for {}", enum_name);
+            let values: Vec<_> = self
+                .values
+                .iter()
+                .map(|(var, value)| (var, value))
+                .collect();
+            const_assert_unique(&title, &quote! { #value_type }, &values, false)
+        });
+        // negative values are written with a sign, `-0x1`, which `from_hex_str` parses back
+        let signed = value_type.to_string().starts_with('i');
+        let to_hex = self.values.iter().map(|(var, value)| {
+            let hex = match eval_discriminant(value) {
+                Some(value) if value < 0 => format!("-{:#x}", value.unsigned_abs()),
+                Some(value) => format!("{:#x}", value),
+                None => {
+                    let sign = match signed {
+                        true => quote! {
+                            const NEGATIVE: bool = VALUE < 0;
+                            const MAGNITUDE: u128 = VALUE.unsigned_abs() as u128;
+                        },
+                        false => quote! {
+                            const NEGATIVE: bool = false;
+                            const MAGNITUDE: u128 = VALUE as u128;
+                        },
+                    };
+                    return quote! {
+                        #enum_name::#var => {
+                            const VALUE: #value_type = #value;
+                            #sign
+                            const BYTES: [u8; ::roset::__private::hex_len(NEGATIVE, MAGNITUDE)] =
+                                ::roset::__private::hex_bytes(NEGATIVE, MAGNITUDE);
+                            const HEX: &str = match ::core::str::from_utf8(&BYTES) {
+                                Ok(hex) => hex,
+                                Err(_) => panic!("hex digits are ascii"),
+                            };
+                            HEX
+                        }
+                    };
+                }
+            };
+            quote! { #enum_name::#var => #hex }
+        });
+
+        quote! {
+            #[allow(deprecated)]
+            impl #enum_name {
+                /// Returns the value declared by `enum_value` of the variant
+                #vis fn value(&self) -> #value_type {
+                    match self {
+                        #(#to_value,)*
+                    }
+                }
+
+                /// Returns the variant declaring the value, if any
+                #vis fn from_value(value: #value_type) -> Option<Self> {
+                    #from_value
+                }
+
+                /// Returns the value as lowercase hex string with `0x` prefix, negative values
+                /// are written with a sign like `-0x1`
+                #vis fn to_hex_str(&self) -> &'static str {
+                    match self {
+                        #(#to_hex,)*
+                    }
+                }
+
+                /// Returns the variant declaring the value written in hex, `0x` prefix is optional
+                /// and may follow a `-` sign
+                #vis fn from_hex_str(s: &str) -> Option<Self> {
+                    let (negative, s) = match s.strip_prefix('-') {
+                        Some(rest) => (true, rest),
+                        None => (false, s),
+                    };
+                    let digits = s
+                        .strip_prefix("0x")
+                        .or_else(|| s.strip_prefix("0X"))
+                        .unwrap_or(s);
+                    if digits.starts_with(['+', '-']) {
+                        return None;
+                    }
+                    let magnitude = u128::from_str_radix(digits, 16).ok()?;
+                    let value = match negative {
+                        true => <#value_type as ::core::convert::TryFrom<i128>>::try_from(
+                            0i128.checked_sub_unsigned(magnitude)?,
+                        )
+                        .ok(),
+                        false => {
+                            <#value_type as ::core::convert::TryFrom<u128>>::try_from(magnitude).ok()
+                        }
+                    };
+                    value.and_then(Self::from_value)
+                }
+            }
+
+            #unique
+        }
+    }
+}
@@ -0,0 +1,3024 @@
+use proc_macro::TokenStream;
+use proc_macro2::Delimiter;
+use proc_macro2::TokenTree;
+use syn::parse_macro_input;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::Attribute;
+use syn::Data;
+use syn::DataEnum;
+use syn::DeriveInput;
+use syn::Expr;
+use syn::ExprLit;
+use syn::ExprParen;
+use syn::ExprUnary;
+use syn::Field;
+use syn::Fields;
+use syn::Ident;
+use syn::Lit;
+use syn::UnOp;
+
+mod attr;
+mod enum_arity;
+mod enum_code;
+mod enum_debug_str;
+mod enum_from;
+mod enum_from_iterator;
+mod enum_from_wrapped;
+mod enum_into_static_set;
+mod enum_into_wrapped;
+mod enum_map;
+mod enum_migrate;
+mod enum_order;
+mod enum_payload_size;
+mod enum_range;
+mod enum_ref;
+mod enum_repr;
+mod enum_tag;
+mod enum_unwrap;
+mod enum_value;
+mod options;
+
+use enum_arity::EnumArity;
+use enum_code::EnumCode;
+use enum_debug_str::EnumDebugStr;
+use enum_from::EnumFrom;
+use enum_from_iterator::EnumFromIterator;
+use enum_from_wrapped::EnumFromWrapped;
+use enum_into_static_set::EnumIntoStaticSet;
+use enum_into_wrapped::EnumIntoWrapped;
+use enum_map::EnumMap;
+use enum_migrate::EnumMigrate;
+use enum_order::EnumOrder;
+use enum_payload_size::EnumPayloadSize;
+use enum_range::EnumRange;
+use enum_ref::EnumRef;
+use enum_repr::EnumRepr;
+use enum_tag::EnumTag;
+use enum_unwrap::EnumUnwrap;
+use enum_value::EnumValue;
+use options::RosetOptions;
+
+/// Implement trait `FromStr` `From<T>` for **specific** variant in `enum` type
+///
+/// - `enum_from(str = "what")` attributes could be used to implement `FromStr` trait and `to_str` method
+/// - `enum_from(str = protocol::NAME)` takes the `str` from a `&str` const, checked unique by a
+///   const assertion, but can not be combined with `namespace`, `sort_str_match`, `http`,
+///   `serde_tag`, `ts`, `async_graphql`, `roset(manifest)`, `strategy = "table"` and the
+///   `{expected}` placeholder, which read the value
+/// - `enum_from(inner)` attributes could be used to implement `From<T>` for specific variant inner type
+/// - `enum_from(inner)` on a variant with several fields implements `From` for a tuple of them,
+///   fields marked `enum_from(skip_field)` are left out and take `Default::default()`
+/// - `enum_from(inner = "0")` or `enum_from(inner = "name")` implements `From` for the selected
+///   field only, other fields take `Default::default()`
+/// - `enum_from(inner_from = "i8")`, repeatable, implements `From<i8>` widening into the payload
+///   with `Into`, for a variant with one field or `inner` selecting one
+/// - `enum_from(str)` and `enum_from(inner)` could be combined on one variant, parsing the str
+///   then fills the payload with `Default::default()`
+/// - `enum_from(default)` attributes could be used on one variant to map unknown strings to it,
+///   `FromStr::Err` is then `Infallible`
+/// - `enum_from(other)` attributes could be used on one variant like `Other(String)` to keep
+///   unknown strings, the variant needs no `str` and `to_str` then borrows from the enum
+/// - `enum_from(flatten)` attributes could be used on a variant like `Inner(InnerEnum)` holding
+///   another roset enum, parsing tries the inner enum's strings and `to_str` delegates to it
+/// - `enum_from(with_matcher = "lookup")` attributes on the enum try `fn lookup(&str) ->
+///   Option<Self>` on input not found in the str mapping, after flattened enums and before
+///   `default` or `other`, for values registered at run time
+/// - `enum_from(os_str)` attributes on the enum could be used to implement `TryFrom<&OsStr>`
+/// - `enum_from(bytes)` attributes on the enum could be used to implement `TryFrom<&[u8]>`,
+///   all str values must be ASCII
+/// - `enum_from(accessor = "as_code")` attributes on the enum rename the generated `to_str`
+///   method, repeat it to generate more than one accessor
+/// - `enum_from(error_owns_input)` attributes on the enum keep the rejected input as `String`
+///   in the parse error, `enum_from(error_owns_input = 16)` keeps up to 16 bytes without allocation
+/// - `enum_from(eq_str)` attributes on the enum implement `PartialEq` with `str` and `&str` both
+///   ways, comparing the `str` of the variant
+/// - `enum_from(error_msg = "unknown animal: {input}")` attributes on the enum reword the not
+///   found parse error, `{expected}` lists the `str` values and `{input}` requires `error_owns_input`
+/// - `enum_from(partial)` attributes on the enum allow `str` on only some variants, `to_str`
+///   then returns `Option<&str>`, the default `enum_from(strict)` requires all variants
+/// - `enum_from(flags)` attributes on unit-only enum generate a `{Enum}Set` bitset, which is
+///   parsed from and formatted to pipe-separated strings like `"READ|WRITE"`
+/// - `enum_from(strum_compat)` attributes on the enum also accept `strum(serialize = "..")`,
+///   `strum(to_string = "..")` as `str` and `strum(default)` as `other`, easing migration from strum;
+///   `to_string` or else the first `serialize` is the `str`, further `serialize` values are only
+///   accepted by `FromStr`, and strum keys roset does not use are left to strum
+/// - `enum_from(rename_all = "kebab-case")` attributes on the enum give variants without `str`
+///   their renamed name as `str`, cases are named like serde: `lowercase`, `UPPERCASE`,
+///   `PascalCase`, `camelCase`, `snake_case`, `SCREAMING_SNAKE_CASE`, `kebab-case` and
+///   `SCREAMING-KEBAB-CASE`
+/// - `enum_from(strip_prefix = "Kind")` attributes on the enum strip the prefix from variant
+///   names before `rename_all`
+/// - `enum_from(prefix = "x-", suffix = "-v1")` attributes on the enum decorate the `str` derived
+///   by `rename_all`
+/// - `enum_from(accept_ordinal)` attributes on the enum make `FromStr` also accept the variant
+///   ordinal, or its discriminant if declared explicitly, as a decimal string
+/// - `enum_from(strict_roundtrip)` attributes on the enum guarantee `to_str` and `FromStr` are
+///   inverse of each other, rejecting `partial`, `accept_ordinal`, `default`, `other`,
+///   `flatten` and payload variants at compile time
+///
+/// Generated code follows variant declaration order, so the output is the same across builds.
+/// `#[roset(sort_str_match)]` on the enum sorts the parsing match arms by string length then
+/// bytes instead, which may help the optimizer with large string tables.
+///
+/// `enum_from(strategy = "table")` on the enum parses by binary search in a sorted table of the
+/// `str` values instead of a `match`, which scales better for large string tables.
+/// `strategy = "auto"` picks the table from 32 `str` values on, `"match"` is the default.
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(strategy = "table", bytes)]
+/// enum Planet {
+///     #[enum_from(str = "mercury")]
+///     Mercury,
+///     #[enum_from(str = "venus")]
+///     Venus,
+///     #[enum_from(str = "earth")]
+///     Earth,
+/// }
+///
+/// assert_eq!("earth".parse::<Planet>(), Ok(Planet::Earth));
+/// assert_eq!(Planet::try_from(&b"venus"[..]), Ok(Planet::Venus));
+/// assert!("pluto".parse::<Planet>().is_err());
+/// ```
+///
+/// A fieldless enum with a `str` on every variant exposes them as `VARIANT_STRS` in declaration
+/// order, and `to_str` indexes this array by the variant instead of matching strings.
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// #[derive(EnumFrom)]
+/// enum Level {
+///     #[enum_from(str = "low")]
+///     Low,
+///     #[enum_from(str = "high")]
+///     High = 8,
+/// }
+///
+/// assert_eq!(Level::VARIANT_STRS, ["low", "high"]);
+/// assert_eq!(Level::High.to_str(), "high");
+/// ```
+///
+/// `str` values may come from consts already defined elsewhere.
+///
+/// ```
+/// use roset::{EnumFrom, EnumValue};
+///
+/// mod protocol {
+///     pub const HTTPS: &str = "https";
+///     pub const PORT_HTTPS: u16 = 443;
+/// }
+///
+/// #[derive(PartialEq, Debug, EnumFrom, EnumValue)]
+/// enum Scheme {
+///     #[enum_from(str = protocol::HTTPS)]
+///     #[enum_value(u16 = protocol::PORT_HTTPS)]
+///     Https,
+///     #[enum_from(str = "http")]
+///     #[enum_value(u16 = 80)]
+///     Http,
+/// }
+///
+/// assert_eq!("https".parse::<Scheme>(), Ok(Scheme::Https));
+/// assert_eq!(Scheme::Https.to_str(), "https");
+/// assert_eq!(Scheme::from_value(443), Some(Scheme::Https));
+/// assert_eq!(Scheme::parse_prefix("https://"), Some((Scheme::Https, "://")));
+/// ```
+///
+/// `with_matcher` hands strings outside the mapping to a function, like aliases loaded from a
+/// config file.
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(with_matcher = "lookup_region")]
+/// enum Region {
+///     #[enum_from(str = "eu")]
+///     Eu,
+///     #[enum_from(str = "us")]
+///     Us,
+/// }
+///
+/// fn lookup_region(s: &str) -> Option<Region> {
+///     s.eq_ignore_ascii_case("europe").then_some(Region::Eu)
+/// }
+///
+/// assert_eq!("eu".parse::<Region>(), Ok(Region::Eu));
+/// assert_eq!("Europe".parse::<Region>(), Ok(Region::Eu));
+/// assert!("asia".parse::<Region>().is_err());
+/// ```
+///
+/// `enum_from(hidden)` on a variant keeps it parsing and printing, but leaves it out of
+/// `VARIANT_STRS`, `METADATA`, `all()` of flags and the `{expected}` list of `error_msg`.
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(error_msg = "expected {expected}")]
+/// enum Mode {
+///     #[enum_from(str = "fast")]
+///     Fast,
+///     #[enum_from(str = "debug", hidden)]
+///     Debug,
+/// }
+///
+/// assert_eq!("debug".parse::<Mode>(), Ok(Mode::Debug));
+/// assert_eq!(Mode::Debug.to_str(), "debug");
+/// assert_eq!(Mode::VARIANT_STRS, ["fast"]);
+/// assert_eq!(Mode::METADATA.len(), 1);
+/// assert_eq!("slow".parse::<Mode>().unwrap_err().to_string(), "expected fast");
+/// ```
+///
+/// `#[roset(gen_tests)]` on the enum emits a `#[cfg(test)]` module testing that every `str`
+/// round-trips through `FromStr` and `to_str`, that no `str` is used twice, and that every
+/// generated `TryFrom`, of `bytes`, `os_str`, `http`, string types and data format values, agrees
+/// with `FromStr`. Test modules can not see items
+/// of a function body, so `gen_tests` requires the enum to be declared at module level, an enum
+/// declared in a function fails with an unresolved import `super::Enum` pointing at `gen_tests`.
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// #[derive(Debug, EnumFrom)]
+/// #[roset(gen_tests)]
+/// #[enum_from(bytes)]
+/// enum Method {
+///     #[enum_from(str = "GET")]
+///     Get,
+///     #[enum_from(str = "POST")]
+///     Post,
+/// }
+///
+/// assert_eq!(Method::Post.to_str(), "POST");
+/// ```
+///
+/// `parse_prefix` matches the longest `str` at the start of the input and returns the variant
+/// with the rest of the input, so the enum can recognize tokens inside a hand-written parser.
+/// A `str` being the prefix of another is warned about, listing the pairs, as the longer one
+/// always wins. `enum_from(allow_prefix_overlap)` on the enum silences the warning.
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(allow_prefix_overlap)]
+/// enum Op {
+///     #[enum_from(str = "<")]
+///     Less,
+///     #[enum_from(str = "<=")]
+///     LessEqual,
+/// }
+///
+/// assert_eq!(Op::parse_prefix("<= 1"), Some((Op::LessEqual, " 1")));
+/// assert_eq!(Op::parse_prefix("< 1"), Some((Op::Less, " 1")));
+/// assert_eq!(Op::parse_prefix("> 1"), None);
+/// ```
+///
+/// `enum_from(str_ids)` on the enum numbers the `str` values in declaration order, with
+/// `str_id`, `from_str_id` and `str_id_of`, so hot data structures can keep a `u32` instead of a
+/// string. `FromStr` then matches the strings once through `str_id_of`.
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(str_ids)]
+/// enum Tag {
+///     #[enum_from(str = "rust")]
+///     Rust,
+///     #[enum_from(str = "go")]
+///     Go,
+/// }
+///
+/// assert_eq!(Tag::Go.str_id(), 1);
+/// assert_eq!(Tag::str_id_of("rust"), Some(0));
+/// assert_eq!(Tag::from_str_id(1), Some(Tag::Go));
+/// assert_eq!("go".parse::<Tag>(), Ok(Tag::Go));
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// #[derive(EnumFrom)]
+/// #[enum_from(eq_str)]
+/// enum Level {
+///     #[enum_from(str = "debug")]
+///     Debug,
+///     #[enum_from(str = "info")]
+///     Info,
+/// }
+///
+/// let level = Level::Debug;
+/// assert!(level == "debug");
+/// assert!("info" != level);
+/// assert!(*"info" == Level::Info);
+/// ```
+///
+/// Enums may be declared in function bodies, including methods and tests, generated types like
+/// `{Enum}ParseError` are then local to the function as well and never collide.
+///
+/// ```
+/// use roset::{EnumFrom, EnumTag};
+///
+/// fn light() -> &'static str {
+///     #[derive(EnumFrom)]
+///     enum Color {
+///         #[enum_from(str = "white")]
+///         White,
+///     }
+///     let _: Result<Color, ColorParseError> = "white".parse();
+///     Color::White.to_str()
+/// }
+///
+/// struct Palette;
+///
+/// impl Palette {
+///     fn dark() -> u16 {
+///         #[derive(EnumFrom, EnumTag)]
+///         enum Color {
+///             #[enum_from(str = "black")]
+///             #[enum_tag(7)]
+///             Black,
+///         }
+///         let _: ColorParseError = "white".parse::<Color>().err().unwrap();
+///         ColorKind::Black.tag()
+///     }
+/// }
+///
+/// assert_eq!(light(), "white");
+/// assert_eq!(Palette::dark(), 7);
+/// ```
+///
+/// `#[roset(cfg = "feature = \"conversions\"")]` on the enum puts everything generated by any
+/// roset derive behind the cfg predicate, so conversions can be optional features of a library.
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// // `any()` is never enabled, so the generated `to_str` does not clash with this one
+/// #[derive(EnumFrom)]
+/// #[roset(cfg = "any()")]
+/// enum Method {
+///     #[enum_from(str = "GET")]
+///     Get,
+/// }
+///
+/// impl Method {
+///     fn to_str(&self) -> &'static str {
+///         "manual"
+///     }
+/// }
+///
+/// assert_eq!(Method::Get.to_str(), "manual");
+/// ```
+///
+/// Impls generated by each derive are grouped in a `const _: () = { .. };` block documented as
+/// `roset: #[derive(EnumFrom)] for Enum`, after the generated types, which keeps the output of
+/// `cargo expand` easy to navigate. `#[roset(dump)]` on the enum writes the code of every roset
+/// derive to `target/roset/{Enum}.{Derive}.rs` of the crate, and setting `ROSET_DUMP_DIR` dumps
+/// all enums to that directory instead, rebuild with `touch` as the env var is not tracked.
+///
+/// Generated types are named after the enum and share its visibility, so they can be re-exported
+/// next to it. `#[roset(..)]` on the enum renames them with `error_name` for `{Enum}ParseError`,
+/// `set_name` for `{Enum}Set`, `variant_error_name` for `{Enum}VariantError`, `repr_error_name`
+/// for `{Enum}ReprError`, `kind_name` for `{Enum}Kind`, `map_name` for `{Enum}Map`,
+/// `migrate_error_name` for `{Enum}MigrateError`, `static_set_name` for `{Enum}StaticSet` and
+/// `ref_name` for `{Enum}Ref`.
+///
+/// ```
+/// use roset::{EnumFrom, EnumTag};
+///
+/// #[derive(EnumFrom, EnumTag)]
+/// #[roset(error_name = "BadColor", kind_name = "ColorTag")]
+/// pub enum Color {
+///     #[enum_from(str = "red")]
+///     #[enum_tag(1)]
+///     Red,
+/// }
+///
+/// let err: BadColor = "blue".parse::<Color>().err().unwrap();
+/// assert_eq!(err, BadColor::NotFound);
+/// assert_eq!(ColorTag::Red.tag(), 1);
+/// ```
+///
+/// Generated methods and consts take the visibility of the enum, `#[roset(vis = "pub(crate)")]`
+/// on the enum gives them another one, keeping the public API of a library intentional while
+/// the generated types and trait impls stay as visible as the enum.
+///
+/// ```
+/// mod palette {
+///     use roset::EnumFrom;
+///
+///     #[derive(EnumFrom)]
+///     #[roset(vis = "pub(super)")]
+///     pub enum Color {
+///         #[enum_from(str = "red")]
+///         Red,
+///     }
+/// }
+///
+/// assert_eq!(palette::Color::Red.to_str(), "red");
+/// ```
+///
+/// ```compile_fail
+/// mod palette {
+///     use roset::EnumFrom;
+///
+///     #[derive(EnumFrom)]
+///     #[roset(vis = "pub(self)")]
+///     pub enum Color {
+///         #[enum_from(str = "red")]
+///         Red,
+///     }
+/// }
+///
+/// // `to_str` is private to `palette`
+/// palette::Color::Red.to_str();
+/// ```
+///
+/// `#[roset(sealed)]` on the enum implements the roset-core traits through a generated
+/// `{Enum}Roset` trait instead, renamed with `sealed_name`, whose supertrait is private. Callers
+/// get `variant_name`, `COUNT` and `as_str` while the library keeps the traits out of its semver
+/// surface. Sealed enums can not be flattened into another enum or derive `EnumDebugStr` and
+/// `EnumFromIterator`, as they rely on `StrRepr`.
+///
+/// ```
+/// mod api {
+///     use roset::EnumFrom;
+///
+///     #[derive(EnumFrom)]
+///     #[roset(sealed)]
+///     pub enum Level {
+///         #[enum_from(str = "low")]
+///         Low,
+///         #[enum_from(str = "high")]
+///         High,
+///     }
+/// }
+///
+/// use api::{Level, LevelRoset};
+///
+/// assert_eq!(Level::High.variant_name(), "High");
+/// assert_eq!(Level::Low.as_str(), "low");
+/// assert_eq!(<Level as LevelRoset>::COUNT, 2);
+/// ```
+///
+/// ```compile_fail
+/// mod api {
+///     use roset::EnumFrom;
+///
+///     #[derive(EnumFrom)]
+///     #[roset(sealed)]
+///     pub enum Level {
+///         #[enum_from(str = "low")]
+///         Low,
+///     }
+/// }
+///
+/// // roset traits are not implemented for sealed enums
+/// fn name<T: roset::VariantName>(value: &T) -> &'static str {
+///     value.variant_name()
+/// }
+/// name(&api::Level::Low);
+/// ```
+///
+/// `#[roset(manifest)]` on the enum has `EnumFrom` emit `ROSET_MANIFEST`, a JSON description of
+/// the variants for code generators of other languages and documentation sites, read back by a
+/// test or a build step. Every variant lists its `name`, `ordinal`, `str`, `discriminant`, integer
+/// `enum_value` as `{"type", "value"}`, `hidden` and `payload`, with `null` for what the macro can
+/// not know, so it can not be combined with `str = CONST`.
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// #[derive(EnumFrom)]
+/// #[roset(manifest)]
+/// #[enum_from(partial)]
+/// pub enum Level {
+///     #[enum_from(str = "low")]
+///     Low = 1,
+///     High,
+/// }
+///
+/// assert_eq!(
+///     Level::ROSET_MANIFEST,
+///     r#"{"name":"Level","variants":["#.to_string()
+///         + r#"{"name":"Low","ordinal":0,"str":"low","discriminant":1,"value":null,"#
+///         + r#""hidden":false,"payload":false},"#
+///         + r#"{"name":"High","ordinal":1,"str":null,"discriminant":2,"value":null,"#
+///         + r#""hidden":false,"payload":false}]}"#
+/// );
+/// ```
+///
+/// `#[roset(report)]` on the enum warns once per roset derive with a summary of the generated
+/// types and impls, to find out which derives clash when several are combined.
+///
+/// Parsing errors are reported by a generated `{Enum}ParseError` type, which implements
+/// `Display` and `core::error::Error`.
+///
+/// Generated `from_str`, `to_str` and parse errors never allocate, except for keeping the input
+/// as `String` with `enum_from(error_owns_input)` or `enum_from(other)`
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::alloc::{GlobalAlloc, Layout, System};
+/// use std::str::FromStr;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// struct Counting;
+/// static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+///
+/// unsafe impl GlobalAlloc for Counting {
+///     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+///         ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+///         unsafe { System.alloc(layout) }
+///     }
+///     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+///         unsafe { System.dealloc(ptr, layout) }
+///     }
+/// }
+///
+/// #[global_allocator]
+/// static GLOBAL: Counting = Counting;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(bytes, accept_ordinal, error_owns_input = 8)]
+/// enum Level {
+///     #[enum_from(str = "low")]
+///     Low,
+///     #[enum_from(str = "high")]
+///     High,
+/// }
+///
+/// let before = ALLOCATIONS.load(Ordering::SeqCst);
+/// assert_eq!(Level::from_str("high"), Ok(Level::High));
+/// assert_eq!(Level::from_str("0"), Ok(Level::Low));
+/// assert_eq!(Level::try_from(&b"low"[..]), Ok(Level::Low));
+/// assert_eq!(Level::High.to_str(), "high");
+/// let err = Level::from_str("medium").unwrap_err();
+/// assert_eq!(err.input(), Some("medium"));
+/// assert_eq!(ALLOCATIONS.load(Ordering::SeqCst), before);
+/// ```
+///
+/// Traits `VariantName` and `EnumCount` are implemented for every variant, and `StrRepr` is
+/// implemented with `enum_from(str)`, so generic code can be written over any roset enum.
+/// `StaticStr` is implemented too unless a variant borrows its str, `VariantName` and
+/// `StaticStr` also hold for references, and `to_str` is reachable through any smart pointer.
+/// `METADATA` lists a `VariantMeta` with name, str, ordinal and whether it has a payload for
+/// every variant.
+///
+/// Note: `enum_from(str)` **must** be used for all variant if you use it in one variant, unless
+/// `enum_from(partial)` is used on the enum
+///
+/// Generated conversions converting one source type twice, like `enum_from(inner)` on two
+/// variants of the same type, are reported together in one error
+///
+/// ```compile_fail
+/// use roset::EnumFrom;
+///
+/// #[derive(EnumFrom)]
+/// #[enum_from(bytes)]
+/// enum Data {
+///     // error: overlapping conversions from &[u8]
+///     #[enum_from(str = "raw", inner)]
+///     Raw(&'static [u8]),
+///     #[enum_from(str = "empty")]
+///     Empty,
+/// }
+/// ```
+///
+/// Attributes may be written by `macro_rules` expansions or wrapped in `cfg_attr`, which the
+/// compiler expands before the derive runs
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// macro_rules! vocabulary {
+///     ($name:ident { $($var:ident = $s:literal),* }) => {
+///         #[derive(Debug, EnumFrom)]
+///         enum $name {
+///             $(#[cfg_attr(all(), enum_from(str = $s))] $var),*
+///         }
+///     };
+/// }
+///
+/// vocabulary!(Level { Low = "low", High = "high" });
+/// assert_eq!(Level::High.to_str(), "high");
+/// ```
+///
+/// Variants are still required to have a str when the predicate of their `cfg_attr` is false,
+/// so strs gated by a feature pair with `#[cfg_attr(not(feature = ".."), enum_from(partial))]`
+/// on the enum, or gate every str and the generated code with `#[roset(cfg = "..")]`
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// // `any()` is never true, like a disabled feature
+/// #[derive(Debug, PartialEq, EnumFrom)]
+/// #[cfg_attr(not(any()), enum_from(partial))]
+/// enum Level {
+///     #[enum_from(str = "low")]
+///     Low,
+///     #[cfg_attr(any(), enum_from(str = "high"))]
+///     High,
+/// }
+///
+/// assert_eq!("low".parse(), Ok(Level::Low));
+/// assert!("high".parse::<Level>().is_err());
+/// assert_eq!(Level::High.to_str(), None);
+/// ```
+///
+/// Unknown keys are rejected with an error listing the supported keys
+///
+/// ```compile_fail
+/// use roset::EnumFrom;
+///
+/// #[derive(EnumFrom)]
+/// enum Animal {
+///     // error: unknown enum_from attribute `stri`
+///     #[enum_from(stri = "🐱")]
+///     Cat,
+/// }
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::str::FromStr;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// enum Animal {
+///     #[enum_from(str = "🐱")]
+///     Cat,
+///     #[enum_from(str = "🐶")]
+///     Dog,
+/// }
+///
+/// assert_eq!(Animal::from_str("🐱"), Ok(Animal::Cat));
+/// assert_eq!(Animal::from_str("🐶"), Ok(Animal::Dog));
+/// assert_eq!((Animal::Cat).to_str(), "🐱");
+/// assert_eq!((Animal::Dog).to_str(), "🐶");
+/// assert_eq!(Animal::from_str("🐭"), Err(AnimalParseError::NotFound));
+///
+/// let err: Box<dyn std::error::Error> = Animal::from_str("🐭").unwrap_err().into();
+/// assert_eq!(err.to_string(), "input does not match any variant of Animal");
+/// ```
+///
+/// ```
+/// use roset::{EnumCount, EnumFrom, StrRepr, VariantName};
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// enum Animal {
+///     #[enum_from(str = "🐱")]
+///     Cat,
+///     #[enum_from(str = "🐶")]
+///     Dog,
+/// }
+///
+/// fn describe<T: StrRepr + VariantName + EnumCount>(value: &T) -> String {
+///     format!("{} {} of {}", value.variant_name(), value.as_str(), T::COUNT)
+/// }
+///
+/// assert_eq!(describe(&Animal::Dog), "Dog 🐶 of 2");
+/// assert_eq!(Animal::from_str_repr("🐱"), Some(Animal::Cat));
+/// ```
+///
+/// ```
+/// use roset::{EnumFrom, StaticStr, VariantName};
+/// use std::rc::Rc;
+///
+/// #[derive(EnumFrom)]
+/// enum Animal {
+///     #[enum_from(str = "🐱")]
+///     Cat,
+/// }
+///
+/// fn label<T: StaticStr + VariantName>(value: T) -> (&'static str, &'static str) {
+///     (T::static_str(&value), value.variant_name())
+/// }
+///
+/// let cat = Rc::new(Animal::Cat);
+/// assert_eq!(cat.to_str(), "🐱");
+/// assert_eq!(label(&*cat), ("🐱", "Cat"));
+/// assert_eq!(label(&&Animal::Cat), ("🐱", "Cat"));
+/// ```
+///
+/// ```
+/// use roset::{EnumFrom, VariantMeta};
+///
+/// #[derive(EnumFrom)]
+/// #[enum_from(partial)]
+/// enum Shape {
+///     #[enum_from(str = "circle")]
+///     Circle(f32),
+///     Empty,
+/// }
+///
+/// assert_eq!(Shape::METADATA.len(), 2);
+/// assert_eq!(
+///     Shape::METADATA[0],
+///     VariantMeta { name: "Circle", str_value: Some("circle"), ordinal: 0, has_payload: true }
+/// );
+/// assert_eq!(Shape::METADATA[1].str_value, None);
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::convert::TryFrom;
+/// use std::ffi::OsStr;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(os_str)]
+/// enum Command {
+///     #[enum_from(str = "build")]
+///     Build,
+///     #[enum_from(str = "test")]
+///     Test,
+/// }
+///
+/// assert_eq!(Command::try_from(OsStr::new("build")), Ok(Command::Build));
+/// assert_eq!(Command::try_from(OsStr::new("run")), Err(CommandParseError::NotFound));
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::convert::TryFrom;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(bytes)]
+/// enum Method {
+///     #[enum_from(str = "GET")]
+///     Get,
+///     #[enum_from(str = "POST")]
+///     Post,
+/// }
+///
+/// let buffer = b"POST /index.html";
+/// assert_eq!(Method::try_from(&buffer[..4]), Ok(Method::Post));
+/// assert_eq!(Method::try_from(&buffer[..3]), Err(MethodParseError::NotFound));
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::str::FromStr;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(accept_ordinal)]
+/// enum Level {
+///     #[enum_from(str = "low")]
+///     Low,
+///     #[enum_from(str = "medium")]
+///     Medium,
+///     #[enum_from(str = "high")]
+///     High = 10,
+///     #[enum_from(str = "extreme")]
+///     Extreme,
+/// }
+///
+/// assert_eq!(Level::from_str("1"), Ok(Level::Medium));
+/// assert_eq!(Level::from_str("10"), Ok(Level::High));
+/// assert_eq!(Level::from_str("11"), Ok(Level::Extreme));
+/// assert_eq!(Level::from_str("2"), Err(LevelParseError::NotFound));
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::convert::Infallible;
+/// use std::str::FromStr;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// enum Browser {
+///     #[enum_from(str = "firefox")]
+///     Firefox,
+///     #[enum_from(str = "chrome")]
+///     Chrome,
+///     #[enum_from(str = "unknown", default)]
+///     Unknown,
+/// }
+///
+/// let browser: Result<Browser, Infallible> = Browser::from_str("lynx");
+/// assert_eq!(browser, Ok(Browser::Unknown));
+/// assert_eq!(Browser::from_str("chrome"), Ok(Browser::Chrome));
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::str::FromStr;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// enum Encoding {
+///     #[enum_from(str = "gzip")]
+///     Gzip,
+///     #[enum_from(other)]
+///     Other(String),
+/// }
+///
+/// assert_eq!(Encoding::from_str("gzip"), Ok(Encoding::Gzip));
+/// assert_eq!(Encoding::from_str("br"), Ok(Encoding::Other("br".to_string())));
+/// assert_eq!(Encoding::Other("br".to_string()).to_str(), "br");
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(accessor = "as_code", accessor = "as_str")]
+/// enum Currency {
+///     #[enum_from(str = "EUR")]
+///     Euro,
+///     #[enum_from(str = "USD")]
+///     Dollar,
+/// }
+///
+/// assert_eq!(Currency::Euro.as_code(), "EUR");
+/// assert_eq!(Currency::Dollar.as_str(), "USD");
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::str::FromStr;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(error_owns_input)]
+/// enum Color {
+///     #[enum_from(str = "red")]
+///     Red,
+/// }
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(error_owns_input = 4)]
+/// enum Shape {
+///     #[enum_from(str = "circle")]
+///     Circle,
+/// }
+///
+/// let err = Color::from_str("blue").unwrap_err();
+/// assert_eq!(err, ColorParseError::NotFound("blue".to_string()));
+/// assert_eq!(err.to_string(), r#"input "blue" does not match any variant of Color"#);
+///
+/// let err = Shape::from_str("square").unwrap_err();
+/// assert_eq!(err.input(), Some("squa"));
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::str::FromStr;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(error_owns_input, error_msg = "unknown animal: {input}, try {expected}")]
+/// enum Animal {
+///     #[enum_from(str = "cat")]
+///     Cat,
+///     #[enum_from(str = "dog")]
+///     Dog,
+/// }
+///
+/// let err = Animal::from_str("cow").unwrap_err();
+/// assert_eq!(err.to_string(), "unknown animal: cow, try cat, dog");
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::str::FromStr;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(partial)]
+/// enum Token {
+///     #[enum_from(str = "+")]
+///     Plus,
+///     #[enum_from(str = "-")]
+///     Minus,
+///     Eof,
+/// }
+///
+/// assert_eq!(Token::from_str("+"), Ok(Token::Plus));
+/// assert_eq!(Token::Minus.to_str(), Some("-"));
+/// assert_eq!(Token::Eof.to_str(), None);
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(flags)]
+/// enum Permission {
+///     #[enum_from(str = "READ")]
+///     Read,
+///     #[enum_from(str = "WRITE")]
+///     Write,
+///     #[enum_from(str = "EXEC")]
+///     Exec,
+/// }
+///
+/// let set: PermissionSet = "WRITE | READ".parse().unwrap();
+/// assert!(set.contains(&Permission::Read));
+/// assert!(!set.contains(&Permission::Exec));
+/// assert_eq!(set.to_string(), "READ|WRITE");
+/// assert_eq!(PermissionSet::all().len(), 3);
+/// assert_eq!("READ|SUDO".parse::<PermissionSet>(), Err(PermissionParseError::NotFound));
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::str::FromStr;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(strum_compat)]
+/// enum Fruit {
+///     #[strum(serialize = "apple")]
+///     Apple,
+///     #[strum(to_string = "pear")]
+///     Pear,
+///     #[enum_from(str = "plum")]
+///     Plum,
+/// }
+///
+/// assert_eq!(Fruit::from_str("apple"), Ok(Fruit::Apple));
+/// assert_eq!(Fruit::Pear.to_str(), "pear");
+/// ```
+///
+/// Attributes shared with strum keep their other keys and several `serialize` aliases
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(strum_compat)]
+/// enum Fruit {
+///     #[strum(serialize = "apple", message = "An apple")]
+///     Apple,
+///     #[strum(serialize = "pear", serialize = "poire", to_string = "Pear")]
+///     Pear,
+/// }
+///
+/// assert_eq!(Fruit::Apple.to_str(), "apple");
+/// assert_eq!(Fruit::Pear.to_str(), "Pear");
+/// assert_eq!("poire".parse(), Ok(Fruit::Pear));
+/// assert_eq!("pear".parse(), Ok(Fruit::Pear));
+/// assert_eq!("Pear".parse(), Ok(Fruit::Pear));
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::str::FromStr;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// enum Color {
+///     #[enum_from(str = "red")]
+///     Red,
+///     #[enum_from(str = "blue")]
+///     Blue,
+/// }
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// enum Style {
+///     #[enum_from(str = "bold")]
+///     Bold,
+///     #[enum_from(flatten)]
+///     Color(Color),
+/// }
+///
+/// assert_eq!(Style::from_str("bold"), Ok(Style::Bold));
+/// assert_eq!(Style::from_str("blue"), Ok(Style::Color(Color::Blue)));
+/// assert_eq!(Style::Color(Color::Red).to_str(), "red");
+/// assert!(Style::from_str("green").is_err());
+/// ```
+///
+/// `enum_from(namespace = "net")` on the enum writes `net.` before each of its `str`, explicit or
+/// renamed, so enums flattened into one vocabulary keep their strings apart.
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(namespace = "net", rename_all = "snake_case")]
+/// enum NetError {
+///     Timeout,
+/// }
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(namespace = "disk", rename_all = "snake_case")]
+/// enum DiskError {
+///     Timeout,
+/// }
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// enum Error {
+///     #[enum_from(flatten)]
+///     Net(NetError),
+///     #[enum_from(flatten)]
+///     Disk(DiskError),
+/// }
+///
+/// assert_eq!(Error::Net(NetError::Timeout).to_str(), "net.timeout");
+/// assert_eq!("disk.timeout".parse(), Ok(Error::Disk(DiskError::Timeout)));
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::str::FromStr;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(rename_all = "snake_case", strip_prefix = "Kind")]
+/// enum Kind {
+///     KindFoo,
+///     KindBarBaz,
+/// }
+///
+/// assert_eq!(Kind::from_str("foo"), Ok(Kind::KindFoo));
+/// assert_eq!(Kind::KindBarBaz.to_str(), "bar_baz");
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(rename_all = "kebab-case", prefix = "x-", suffix = "-v1")]
+/// enum Header {
+///     RequestId,
+///     TraceParent,
+/// }
+///
+/// assert_eq!(Header::from_str("x-request-id-v1"), Ok(Header::RequestId));
+/// assert_eq!(Header::TraceParent.to_str(), "x-trace-parent-v1");
+/// ```
+///
+/// The `str` of a variant is resolved in order:
+///
+/// 1. `enum_from(str = "..")` on the variant, used verbatim
+/// 2. `strum(serialize = "..")` or `strum(to_string = "..")` with `enum_from(strum_compat)`
+/// 3. the variant name with `strip_prefix`, `rename_all`, then `prefix` and `suffix` applied
+///
+/// `other` and `flatten` variants take no `str`, `rename_all` skips them.
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::str::FromStr;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(rename_all = "lowercase", prefix = "x-")]
+/// enum Header {
+///     Forwarded,
+///     #[enum_from(str = "Content-Type")]
+///     ContentType,
+///     #[enum_from(other)]
+///     Custom(String),
+/// }
+///
+/// assert_eq!(Header::Forwarded.to_str(), "x-forwarded");
+/// assert_eq!(Header::ContentType.to_str(), "Content-Type");
+/// assert_eq!(Header::from_str("x-other"), Ok(Header::Custom("x-other".into())));
+/// ```
+///
+/// Empty strings, and strings used by two variants once resolved, are rejected
+///
+/// ```compile_fail
+/// use roset::EnumFrom;
+///
+/// #[derive(EnumFrom)]
+/// #[enum_from(rename_all = "lowercase")]
+/// enum Animal {
+///     Cat,
+///     // error: str "cat" already used by Cat
+///     CAT,
+/// }
+/// ```
+///
+/// A variant takes each attribute once, a second `str` is an error rather than an alias
+///
+/// ```compile_fail
+/// use roset::EnumFrom;
+///
+/// #[derive(EnumFrom)]
+/// enum Animal {
+///     // error: enum_from(str) used twice on the variant
+///     #[enum_from(str = "cat")]
+///     #[enum_from(str = "kitty")]
+///     Cat,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use roset::EnumFrom;
+///
+/// #[derive(EnumFrom)]
+/// #[enum_from(strict_roundtrip)]
+/// enum Mode {
+///     #[enum_from(str = "fast")]
+///     Fast,
+///     // error: strict_roundtrip forbids default, unknown strings parse to it
+///     #[enum_from(str = "safe", default)]
+///     Safe,
+/// }
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// #[derive(PartialEq, Debug)]
+/// struct Complex {
+///     real: i64,
+///     imag: i64,
+/// }
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// enum Number {
+///     #[enum_from(inner)]
+///     Integer(i32),
+///     #[enum_from(inner)]
+///     Complex(Complex),
+///     // #[enum_from(inner)]
+///     Float(f64),
+/// }
+/// assert_eq!(Number::from(1), Number::Integer(1));
+/// assert_eq!(Number::from(Complex{real: 1, imag: -1}), Number::Complex(Complex{real: 1, imag: -1}));
+///
+/// // error: the trait `From<{float}>` is not implemented for `Number`
+/// // Number::from(1.0);
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::time::Duration;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// enum Event {
+///     #[enum_from(inner)]
+///     Resize(u32, u32),
+///     #[enum_from(inner)]
+///     Timeout(Duration, #[enum_from(skip_field)] u8),
+///     #[enum_from(inner)]
+///     Key {
+///         code: char,
+///         #[enum_from(skip_field)]
+///         repeat: bool,
+///     },
+/// }
+///
+/// assert_eq!(Event::from((80, 24)), Event::Resize(80, 24));
+/// assert_eq!(Event::from(Duration::ZERO), Event::Timeout(Duration::ZERO, 0));
+/// assert_eq!(Event::from('q'), Event::Key { code: 'q', repeat: false });
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::time::Duration;
+///
+/// #[derive(PartialEq, Debug, Default)]
+/// struct RetryPolicy {
+///     attempts: u8,
+/// }
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// enum Error {
+///     #[enum_from(inner = "0")]
+///     Timeout(Duration, RetryPolicy),
+///     #[enum_from(inner = "message")]
+///     Other { code: u16, message: String },
+/// }
+///
+/// let timeout = Duration::from_secs(1);
+/// assert_eq!(Error::from(timeout), Error::Timeout(timeout, RetryPolicy::default()));
+/// assert_eq!(
+///     Error::from("oops".to_string()),
+///     Error::Other { code: 0, message: "oops".to_string() }
+/// );
+/// ```
+///
+/// `enum_from(inner_from = "..")` converts a narrower type into the payload through its `From`
+///
+/// ```
+/// use roset::EnumFrom;
+///
+/// #[derive(Debug, PartialEq, EnumFrom)]
+/// enum Number {
+///     #[enum_from(inner, inner_from = "i8", inner_from = "i16")]
+///     Integer(i64),
+///     #[enum_from(inner_from = "f32")]
+///     Float(f64),
+/// }
+///
+/// assert_eq!(Number::from(-1i8), Number::Integer(-1));
+/// assert_eq!(Number::from(300i16), Number::Integer(300));
+/// assert_eq!(Number::from(7i64), Number::Integer(7));
+/// assert_eq!(Number::from(0.5f32), Number::Float(0.5));
+/// ```
+///
+/// ```
+/// use roset::EnumFrom;
+/// use std::str::FromStr;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// enum Value {
+///     #[enum_from(str = "int", inner)]
+///     Integer(i32),
+///     #[enum_from(str = "text")]
+///     #[enum_from(inner)]
+///     Text(String),
+///     #[enum_from(str = "none")]
+///     Nothing,
+/// }
+///
+/// assert_eq!(Value::from(3), Value::Integer(3));
+/// assert_eq!(Value::from("a".to_string()), Value::Text("a".to_string()));
+/// assert_eq!(Value::Integer(3).to_str(), "int");
+/// assert_eq!(Value::from_str("int"), Ok(Value::Integer(0)));
+/// ```
+///
+/// With the `json` feature, `enum_from(json)` on the enum implements `TryFrom<serde_json::Value>`
+/// from strings, and numbers with `accept_ordinal`, and `From<Enum>` for `serde_json::Value`.
+/// Other value types are rejected with `{Enum}ParseError::UnexpectedType`.
+/// `enum_from(toml)` and `enum_from(yaml)` do the same for `toml::Value` and `serde_yaml::Value`
+/// behind the `toml` and `yaml` features, toml has no null so it can not be combined with `partial`.
+///
+/// ```ignore
+/// use roset::EnumFrom;
+/// use serde_json::{json, Value};
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(json, accept_ordinal)]
+/// enum Level {
+///     #[enum_from(str = "low")]
+///     Low,
+///     #[enum_from(str = "high")]
+///     High,
+/// }
+///
+/// assert_eq!(Level::try_from(json!("high")), Ok(Level::High));
+/// assert_eq!(Level::try_from(&json!(0)), Ok(Level::Low));
+/// assert_eq!(Level::try_from(json!(true)), Err(LevelParseError::UnexpectedType));
+/// assert_eq!(Value::from(Level::Low), json!("low"));
+/// ```
+///
+/// With the `http` feature, `enum_from(http)` implements `TryFrom<&http::HeaderValue>` and
+/// `From<Enum>` for `http::HeaderValue`. Every str must be a valid header value.
+///
+/// ```ignore
+/// use http::HeaderValue;
+/// use roset::EnumFrom;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(http)]
+/// enum Encoding {
+///     #[enum_from(str = "gzip")]
+///     Gzip,
+///     #[enum_from(str = "br")]
+///     Brotli,
+/// }
+///
+/// let header = HeaderValue::from_static("br");
+/// assert_eq!(Encoding::try_from(&header), Ok(Encoding::Brotli));
+/// assert_eq!(HeaderValue::from(Encoding::Gzip), "gzip");
+/// ```
+///
+/// With the `defmt` feature, `enum_from(defmt)` implements `defmt::Format` writing the `str` of
+/// the variant as format string, which defmt interns, so logging costs only an index on the wire.
+///
+/// ```ignore
+/// use roset::EnumFrom;
+///
+/// #[derive(EnumFrom)]
+/// #[enum_from(defmt)]
+/// enum LinkState {
+///     #[enum_from(str = "up")]
+///     Up,
+///     #[enum_from(str = "down")]
+///     Down,
+/// }
+///
+/// defmt::info!("link {}", LinkState::Up);
+/// ```
+///
+/// With the `smol_str` or `compact_str` feature, `enum_from(smol_str)` and `enum_from(compact_str)`
+/// implement `From<Enum>` for `SmolStr` or `CompactString`, and `TryFrom` of both back through
+/// `FromStr`.
+///
+/// ```ignore
+/// use roset::EnumFrom;
+/// use smol_str::SmolStr;
+///
+/// #[derive(PartialEq, Debug, EnumFrom)]
+/// #[enum_from(smol_str)]
+/// enum Region {
+///     #[enum_from(str = "eu-west")]
+///     EuWest,
+///     #[enum_from(str = "us-east")]
+///     UsEast,
+/// }
+///
+/// assert_eq!(SmolStr::from(Region::EuWest), "eu-west");
+/// assert_eq!(Region::try_from(SmolStr::new("us-east")), Ok(Region::UsEast));
+/// ```
+///
+/// With the `serde` feature, `enum_from(serde_as_str)` generates a `{enum}_as_str` module with
+/// `serialize` and `deserialize` for `#[serde(with = "..")]`, so fields keep the str mapping
+/// under the user's own serde derives. The enum must be declared at module level.
+///
+/// ```ignore
+/// use roset::EnumFrom;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(EnumFrom)]
+/// #[enum_from(serde_as_str)]
+/// enum LogLevel {
+///     #[enum_from(str = "info")]
+///     Info,
+///     #[enum_from(str = "debug")]
+///     Debug,
+/// }
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Config {
+///     #[serde(with = "log_level_as_str")]
+///     level: LogLevel,
+/// }
+/// ```
+///
+/// With the `web` feature, `enum_from(web)` implements `Deserialize` from the `str`, so the enum
+/// can be an axum or actix `Path` and `Query` parameter. Rejected input is reported as an unknown
+/// variant listing the expected `str` values. The host crate depends on serde.
+///
+/// ```ignore
+/// use axum::extract::Path;
+/// use roset::EnumFrom;
+///
+/// #[derive(EnumFrom)]
+/// #[enum_from(web)]
+/// enum Sort {
+///     #[enum_from(str = "asc")]
+///     Asc,
+///     #[enum_from(str = "desc")]
+///     Desc,
+/// }
+///
+/// async fn list(Path(sort): Path<Sort>) -> &'static str {
+///     sort.to_str()
+/// }
+/// ```
+///
+/// With the `ts` feature, `enum_from(ts)` generates `ts_union` and `json_schema` returning the
+/// `str` values but hidden ones as a TypeScript union and a JSON Schema, so frontend types can be
+/// generated from the enum by a test or a build step.
+///
+/// ```ignore
+/// use roset::EnumFrom;
+///
+/// #[derive(EnumFrom)]
+/// #[enum_from(ts)]
+/// enum Animal {
+///     #[enum_from(str = "cat")]
+///     Cat,
+///     #[enum_from(str = "dog")]
+///     Dog,
+/// }
+///
+/// assert_eq!(Animal::ts_union(), r#""cat" | "dog""#);
+/// assert_eq!(Animal::json_schema(), r#"{"type":"string","enum":["cat","dog"]}"#);
+/// ```
+///
+/// With the `async_graphql` feature, `enum_from(async_graphql)` implements async-graphql
+/// `InputType` and `OutputType` for enums of unit variants, naming the GraphQL items by the `str`
+/// values instead of a second set of `#[graphql(name)]` attributes. The impls delegate to a
+/// hidden enum derived by async-graphql, the host crate depends on async-graphql.
+///
+/// ```ignore
+/// use roset::EnumFrom;
+///
+/// #[derive(EnumFrom)]
+/// #[enum_from(async_graphql)]
+/// enum Role {
+///     #[enum_from(str = "ADMIN")]
+///     Admin,
+///     #[enum_from(str = "GUEST")]
+///     Guest,
+/// }
+///
+/// struct Query;
+///
+/// #[async_graphql::Object]
+/// impl Query {
+///     async fn role(&self) -> Role {
+///         Role::Admin
+///     }
+/// }
+/// ```
+///
+/// `enum_from(serde_tag = "type")` implements `Serialize` and `Deserialize` for enums with payload
+/// variants, tagged internally by the `str` of the variant, and `serde_content = "data"` tags
+/// adjacently instead. The impls go through enums derived by serde, which needs its `derive`
+/// feature, and payloads need `Default` as for any `str` on a payload variant.
+///
+/// ```ignore
+/// use roset::EnumFrom;
+///
+/// #[derive(Debug, PartialEq, EnumFrom)]
+/// #[enum_from(serde_tag = "type", serde_content = "data")]
+/// enum Shape {
+///     #[enum_from(str = "circle")]
+///     Circle(f64),
+///     #[enum_from(str = "rect")]
+///     Rect { width: f64, height: f64 },
+/// }
+///
+/// let json = serde_json::to_string(&Shape::Circle(1.5)).unwrap();
+/// assert_eq!(json, r#"{"type":"circle","data":1.5}"#);
+/// let shape: Shape = serde_json::from_str(r#"{"type":"rect","data":{"width":1,"height":2}}"#).unwrap();
+/// assert_eq!(shape, Shape::Rect { width: 1.0, height: 2.0 });
+/// ```
+#[proc_macro_derive(EnumFrom, attributes(enum_from, strum, roset))]
+pub fn enum_from(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs) = (input.vis.clone(), input.attrs.clone());
+    let (id, data) = assert_enum("EnumFrom", input);
+    let mut handler = EnumFrom::new(vis, id.clone(), attrs.clone(), data);
+    match handler.parse_attributes() {
+        Ok(_) => finish("EnumFrom", &id, &attrs, handler.write_output()),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Implement `From<T>` for every variant inner type in `enum`
+///
+/// ```
+/// use roset::EnumFromWrapped;
+///
+/// #[derive(PartialEq, Debug, EnumFromWrapped)]
+/// enum Number {
+///     Integer(i32),
+///     Float(f64),
+/// }
+///
+/// assert_eq!(Number::from(1), Number::Integer(1));
+/// assert_eq!(Number::from(1.0), Number::Float(1.0));
+/// ```
+///
+/// Unit variants are skipped. Generic payloads get generic impls, an impl which would overlap
+/// another one is not generated, like `From<T>` beside `From<i32>` or two payloads of one type
+///
+/// ```
+/// use roset::EnumFromWrapped;
+///
+/// #[derive(PartialEq, Debug, EnumFromWrapped)]
+/// enum Maybe<T> {
+///     Some(T),
+///     Nothing,
+/// }
+///
+/// assert_eq!(Maybe::from(1), Maybe::Some(1));
+///
+/// #[derive(PartialEq, Debug, EnumFromWrapped)]
+/// enum Either<T> {
+///     // no From<T>, it would overlap From<u8>
+///     Any(T),
+///     Byte(u8),
+///     Many(Vec<T>),
+/// }
+///
+/// assert_eq!(Either::<char>::from(1u8), Either::Byte(1));
+/// assert_eq!(Either::from(vec!['a']), Either::Many(vec!['a']));
+/// ```
+///
+/// Variants with several fields take a tuple of them, references borrow with the enum lifetimes
+///
+/// ```
+/// use roset::EnumFromWrapped;
+///
+/// #[derive(PartialEq, Debug, EnumFromWrapped)]
+/// enum Token<'a> {
+///     Word(&'a str),
+///     Pair(&'a str, &'a str),
+///     Point(i32, i32, i32),
+/// }
+///
+/// assert_eq!(Token::from(("k", "v")), Token::Pair("k", "v"));
+/// assert_eq!(Token::from((1, 2, 3)), Token::Point(1, 2, 3));
+/// ```
+///
+/// Payloads may be trait objects, with higher-ranked lifetimes and auto trait bounds
+///
+/// ```
+/// use roset::{EnumFromWrapped, EnumIntoWrapped};
+///
+/// type Parser = Box<dyn for<'a> Fn(&'a str) -> usize + Send + Sync>;
+///
+/// #[derive(EnumFromWrapped, EnumIntoWrapped)]
+/// enum Handler {
+///     Callback(Box<dyn Fn()>),
+///     Parser(Box<dyn for<'a> Fn(&'a str) -> usize + Send + Sync>),
+/// }
+///
+/// let handler = Handler::from(Box::new(|s: &str| s.len()) as Parser);
+/// let parser: Parser = handler.try_into().ok().unwrap();
+/// assert_eq!(parser("abc"), 3);
+/// ```
+///
+/// Generated impls allow `deprecated` internally, so deprecated variants do not warn at the
+/// derive site. Trait impls can not carry `#[deprecated]` themselves.
+///
+/// ```
+/// #![deny(deprecated)]
+/// use roset::{EnumFromWrapped, EnumIntoWrapped};
+///
+/// #[derive(PartialEq, Debug, EnumFromWrapped, EnumIntoWrapped)]
+/// enum Number {
+///     Integer(i32),
+///     #[deprecated]
+///     Float(f64),
+/// }
+///
+/// assert_eq!(Number::from(1), Number::Integer(1));
+/// ```
+///
+/// `enum_from_wrapped(transitive = "T")` on a variant also implements `From<T>` through the
+/// variant payload, which must implement `From<T>` itself. Types converted twice, or the enum
+/// itself, are rejected so the chain can not loop.
+///
+/// ```
+/// use roset::EnumFromWrapped;
+///
+/// #[derive(Debug, EnumFromWrapped)]
+/// enum DbError {
+///     Io(std::io::Error),
+/// }
+///
+/// #[derive(Debug, EnumFromWrapped)]
+/// enum AppError {
+///     #[enum_from_wrapped(transitive = "std::io::Error")]
+///     Db(DbError),
+///     Parse(std::num::ParseIntError),
+/// }
+///
+/// let err = AppError::from(std::io::Error::other("disk"));
+/// assert!(matches!(err, AppError::Db(DbError::Io(_))));
+/// ```
+///
+/// `#[enum_error]` on the enum also implements `Display` and `core::error::Error`. Variants with
+/// `enum_error(display = "..")` are formatted with it, `{0}` being the payload, and report the
+/// payload as `source`. Other variants are transparent, forwarding both to the payload.
+///
+/// ```
+/// use roset::EnumFromWrapped;
+/// use std::error::Error;
+///
+/// #[derive(Debug, EnumFromWrapped)]
+/// #[enum_error]
+/// enum AppError {
+///     #[enum_error(display = "invalid number: {0}")]
+///     Parse(std::num::ParseIntError),
+///     #[enum_error(display = "io failed")]
+///     Io(std::io::Error),
+///     Fmt(std::fmt::Error),
+/// }
+///
+/// let err = AppError::from("x".parse::<i32>().unwrap_err());
+/// assert_eq!(err.to_string(), "invalid number: invalid digit found in string");
+/// assert!(err.source().is_some());
+///
+/// let err = AppError::from(std::fmt::Error);
+/// assert_eq!(err.to_string(), std::fmt::Error.to_string());
+/// assert!(err.source().is_none());
+/// ```
+///
+/// `enum_from_wrapped(const_fn)` on the enum also generates `#[must_use] const fn from_{variant}`
+/// constructors, usable in const initializers where `From::from` is not. Tuple variants take
+/// one argument per field.
+///
+/// ```
+/// use roset::EnumFromWrapped;
+///
+/// #[derive(PartialEq, Debug, EnumFromWrapped)]
+/// #[enum_from_wrapped(const_fn)]
+/// enum Limit {
+///     Bytes(u64),
+///     Items(u32),
+/// }
+///
+/// const DEFAULT_LIMIT: Limit = Limit::from_bytes(1024);
+/// assert_eq!(DEFAULT_LIMIT, Limit::from(1024u64));
+/// ```
+///
+/// `enum_from_wrapped(newtype)` on an enum with one single-field variant also converts it back
+/// into the payload and implements `AsRef` and `AsMut`, treating it as a newtype that may grow
+/// variants later. `enum_from_wrapped(deref)` adds `Deref` and `DerefMut` to the payload.
+///
+/// ```
+/// use roset::EnumFromWrapped;
+///
+/// #[derive(EnumFromWrapped)]
+/// #[enum_from_wrapped(newtype, deref)]
+/// enum UserId {
+///     V1(String),
+/// }
+///
+/// let id = UserId::from("alice".to_string());
+/// assert_eq!(id.len(), 5);
+/// assert_eq!(AsRef::<String>::as_ref(&id), "alice");
+/// assert_eq!(String::from(id), "alice");
+/// ```
+///
+/// `PhantomData` fields are not part of the payload and are filled in by the conversion, so
+/// a variant holding only `PhantomData` gets no `From` impl. Fields are recognized by the type
+/// as written, an alias of `PhantomData` is a payload.
+/// `enum_from_wrapped(include_phantom)` on a variant keeps them in the payload.
+///
+/// ```
+/// use roset::EnumFromWrapped;
+/// use std::marker::PhantomData;
+///
+/// struct Meters;
+///
+/// #[derive(PartialEq, Debug, EnumFromWrapped)]
+/// enum Length<U> {
+///     Value(f64, PhantomData<U>),
+///     Marker(PhantomData<U>),
+/// }
+///
+/// assert!(matches!(Length::<Meters>::from(2.0), Length::Value(v, _) if v == 2.0));
+/// ```
+#[proc_macro_derive(EnumFromWrapped, attributes(enum_from_wrapped, enum_error, roset))]
+pub fn enum_from_wrapped(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs, generics) = (
+        input.vis.clone(),
+        input.attrs.clone(),
+        input.generics.clone(),
+    );
+    let (id, data) = assert_enum("EnumFromWrapped", input);
+    let mut handler = EnumFromWrapped::new(vis, id.clone(), attrs.clone(), generics, data);
+    match handler.parse_attributes() {
+        Ok(_) => finish("EnumFromWrapped", &id, &attrs, handler.write_output()),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Implement `TryFrom<T>` for every variant inner type in `enum`, and `TryFrom<&mut T>` for
+/// mutable references to it
+///
+/// A `fold` method taking a closure `on_{variant}` per variant is also generated, so adding a
+/// variant breaks every call site instead of sneaking through a wildcard match
+///
+/// Accessors `is_{variant}`, `as_{variant}` and `try_into_{variant}` are generated for every
+/// variant, carrying the variant doc comments and `#[deprecated]`. `try_into_{variant}` needs
+/// no type annotation and gives the value back on mismatch.
+///
+/// Conversion errors are reported by a generated `{Enum}VariantError` type, which implements
+/// `Display` and `core::error::Error`.
+///
+/// ```
+/// use roset::EnumIntoWrapped;
+/// use std::convert::{TryFrom, TryInto};
+///
+/// #[derive(PartialEq, Debug, EnumIntoWrapped)]
+/// enum Number {
+///     Integer(i32),
+///     Float(f64),
+/// }
+///
+/// let a = Number::Integer(1);
+/// assert_eq!(a.try_into(), Ok(1));
+///
+/// let b: Result<f64, NumberVariantError> = Number::Float(1.0).try_into();
+/// assert!(b.is_ok());
+///
+/// let c: Result<i32, NumberVariantError> = Number::Float(1.0).try_into();
+/// let err = c.unwrap_err();
+/// assert_eq!(err.found, "Float");
+/// assert_eq!(err.to_string(), "expected variant Number::Integer, found Number::Float");
+///
+/// let mut d = Number::Integer(1);
+/// if let Ok(inner) = <&mut i32>::try_from(&mut d) {
+///     *inner += 1;
+/// }
+/// assert_eq!(d, Number::Integer(2));
+///
+/// let e = Number::Float(1.5).fold(|int| int as f64, |float| float * 2.0);
+/// assert_eq!(e, 3.0);
+///
+/// assert!(Number::Float(1.0).is_float());
+/// assert_eq!(Number::Integer(1).as_integer(), Some(&1));
+/// assert_eq!(Number::Integer(1).as_float(), None);
+/// assert_eq!(Number::Integer(1).try_into_integer(), Ok(1));
+/// assert_eq!(Number::Integer(1).try_into_float(), Err(Number::Integer(1)));
+/// ```
+///
+/// `enum_into_wrapped(infallible)` on one variant implements `From<Enum>` instead of
+/// `TryFrom<Enum>` for its payload, for newtype-like enums kept open to new variants. The
+/// conversion panics on other variants, which the user guarantees never to convert.
+///
+/// ```
+/// use roset::EnumIntoWrapped;
+///
+/// #[derive(EnumIntoWrapped)]
+/// enum Config {
+///     #[enum_into_wrapped(infallible)]
+///     V1(String),
+/// }
+///
+/// let path: String = Config::V1("a.toml".to_string()).into();
+/// assert_eq!(path, "a.toml");
+/// ```
+///
+/// `enum_into_wrapped(into_option)` on a variant also implements `From<Enum>` for `Option` of
+/// its payload, `None` for other variants, which reads well in `filter_map(Into::into)`.
+///
+/// ```
+/// use roset::EnumIntoWrapped;
+///
+/// #[derive(EnumIntoWrapped)]
+/// enum Event {
+///     #[enum_into_wrapped(into_option)]
+///     Key(char),
+///     Click(u32),
+/// }
+///
+/// let events = vec![Event::Key('a'), Event::Click(1), Event::Key('b')];
+/// let keys: Vec<char> = events.into_iter().filter_map(Into::into).collect();
+/// assert_eq!(keys, ['a', 'b']);
+/// ```
+///
+/// `enum_into_wrapped(extractors)` on the enum generates an associated function per variant,
+/// named by its plural like `integers` for `Integer`, keeping only its payloads from an iterator.
+///
+/// ```
+/// use roset::EnumIntoWrapped;
+///
+/// #[derive(EnumIntoWrapped)]
+/// #[enum_into_wrapped(extractors)]
+/// enum Token {
+///     Integer(i32),
+///     Identity(String),
+///     Punct(char),
+/// }
+///
+/// let tokens = || vec![Token::Integer(1), Token::Punct('+'), Token::Integer(2)];
+/// assert_eq!(Token::integers(tokens()).sum::<i32>(), 3);
+/// assert_eq!(Token::puncts(tokens()).collect::<String>(), "+");
+/// assert_eq!(Token::identities(tokens()).count(), 0);
+/// ```
+///
+/// `enum_into_wrapped(compat_unit_error)` on the enum implements `From<{Enum}VariantError>` for
+/// `()`, so code written against the former `()` error keeps compiling with `?` while it is
+/// migrated to the variant error. It also adds a deprecated inherent `try_into` with the `()`
+/// error, which takes precedence over `TryInto::try_into`, so `value.try_into()` keeps its old
+/// error type and warns until it is moved to `TryFrom`.
+///
+/// ```
+/// use roset::EnumIntoWrapped;
+///
+/// #[derive(EnumIntoWrapped)]
+/// #[enum_into_wrapped(compat_unit_error)]
+/// enum Value {
+///     Int(i64),
+///     Text(String),
+/// }
+///
+/// fn legacy(value: Value) -> Result<i64, ()> {
+///     Ok(i64::try_from(value)?)
+/// }
+///
+/// assert_eq!(legacy(Value::Int(1)), Ok(1));
+/// assert_eq!(legacy(Value::Text("a".into())), Err(()));
+///
+/// // warning: use of deprecated method `Value::try_into`
+/// #[allow(deprecated)]
+/// let text: Result<String, ()> = Value::Int(1).try_into();
+/// assert_eq!(text, Err(()));
+///
+/// let text = String::try_from(Value::Int(1));
+/// assert_eq!(text.unwrap_err().found, "Int");
+/// ```
+///
+/// Payload types are used as written, so type aliases and generic aliases need no special
+/// handling, the generated impls name the alias and the compiler resolves it.
+///
+/// ```
+/// use roset::{EnumFrom, EnumIntoWrapped};
+///
+/// type Bytes = Vec<u8>;
+/// type Pair<T> = (T, T);
+///
+/// #[derive(PartialEq, Debug, EnumFrom, EnumIntoWrapped)]
+/// enum Frame {
+///     #[enum_from(inner)]
+///     Data(Bytes),
+///     #[enum_from(inner)]
+///     Range(Pair<u32>),
+/// }
+///
+/// assert_eq!(Frame::from(vec![1u8]), Frame::Data(vec![1]));
+/// assert_eq!(Bytes::try_from(Frame::Data(vec![2])), Ok(vec![2]));
+/// assert_eq!(Pair::<u32>::try_from(Frame::from((1, 3))), Ok((1, 3)));
+/// assert_eq!(Frame::Range((0, 1)).as_range(), Some(&(0, 1)));
+/// ```
+#[proc_macro_derive(EnumIntoWrapped, attributes(enum_into_wrapped, roset))]
+pub fn enum_into_wrapped(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs) = (input.vis.clone(), input.attrs.clone());
+    let (id, data) = assert_enum("EnumIntoWrapped", input);
+    let mut handler = EnumIntoWrapped::new(vis, id.clone(), attrs.clone(), data);
+    match handler.parse_attributes() {
+        Ok(_) => finish("EnumIntoWrapped", &id, &attrs, handler.write_output()),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Implement `to_repr`, `from_repr`, `TryFrom<repr>` and `From<Enum> for repr` for unit-only
+/// `enum` with an integer `#[repr(..)]`, out-of-range values are rejected by `{Enum}ReprError`
+///
+/// ```
+/// use roset::EnumRepr;
+///
+/// #[derive(PartialEq, Debug, EnumRepr)]
+/// #[repr(u8)]
+/// enum Opcode {
+///     Nop,
+///     Load = 0x10,
+///     Store,
+/// }
+///
+/// assert_eq!(Opcode::Store.to_repr(), 0x11);
+/// assert_eq!(Opcode::from_repr(0x10), Some(Opcode::Load));
+/// assert_eq!(Opcode::try_from(2).unwrap_err().value, 2);
+/// assert_eq!(u8::from(Opcode::Nop), 0);
+/// ```
+///
+/// `enum_repr(deny_unknown_variants)` on the enum checks values against the ranges of literal
+/// discriminants, listed in `REPR_RANGES` and in the error message, and adds
+/// `unsafe fn from_repr_unchecked` for decoders which validated the value already.
+///
+/// ```
+/// use roset::EnumRepr;
+///
+/// #[derive(PartialEq, Debug, EnumRepr)]
+/// #[repr(u8)]
+/// #[enum_repr(deny_unknown_variants)]
+/// enum Opcode {
+///     Nop,
+///     Jump,
+///     Load = 0x10,
+///     Store,
+///     Halt = 0xff,
+/// }
+///
+/// assert_eq!(Opcode::from_repr(0x11), Some(Opcode::Store));
+/// assert_eq!(Opcode::REPR_RANGES, &[0..=1, 16..=17, 255..=255]);
+/// let err = Opcode::try_from(3).unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "value 3 does not match any variant of Opcode, expected 0..=1, 16..=17, 255"
+/// );
+/// // SAFETY: 0x10 is the discriminant of Load
+/// assert_eq!(unsafe { Opcode::from_repr_unchecked(0x10) }, Opcode::Load);
+/// ```
+///
+/// With the `bytemuck` feature, `enum_repr(bytemuck)` on the enum also implements
+/// `bytemuck::CheckedBitPattern` and `bytemuck::NoUninit`, so values can be read out of byte
+/// buffers with `bytemuck::checked` functions. The enum must be `Copy`.
+///
+/// ```ignore
+/// #[derive(Clone, Copy, EnumRepr)]
+/// #[repr(u8)]
+/// #[enum_repr(bytemuck)]
+/// enum Opcode {
+///     Nop,
+///     Load,
+/// }
+///
+/// let op: &Opcode = bytemuck::checked::from_bytes(&[1]);
+/// ```
+#[proc_macro_derive(EnumRepr, attributes(enum_repr, roset))]
+pub fn enum_repr(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs) = (input.vis.clone(), input.attrs.clone());
+    let (id, data) = assert_enum("EnumRepr", input);
+    let mut handler = EnumRepr::new(vis, id.clone(), attrs.clone(), data);
+    match handler.parse_attributes() {
+        Ok(_) => finish("EnumRepr", &id, &attrs, handler.write_output()),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Implement `tag` and `from_tag` for `enum` with a `u16` wire tag declared by `#[enum_tag(5)]` on
+/// every variant, so reordering variants never changes the tags of a versioned binary format
+///
+/// A fieldless `{Enum}Kind` mirrors the variants, `from_tag` returns it as payloads can not be
+/// made up from a tag, and `kind` gives the kind of a value.
+///
+/// ```
+/// use roset::EnumTag;
+///
+/// #[derive(EnumTag)]
+/// enum Message {
+///     #[enum_tag(7)]
+///     Close,
+///     #[enum_tag(5)]
+///     Data(Vec<u8>),
+/// }
+///
+/// assert_eq!(Message::Data(vec![]).tag(), 5);
+/// assert_eq!(Message::from_tag(7), Some(&MessageKind::Close));
+/// assert_eq!(Message::from_tag(6), None);
+/// assert_eq!(Message::Close.kind().tag(), 7);
+/// ```
+///
+/// Tags must be unique. On the enum, `enum_tag(contiguous)` also rejects gaps between tags, and
+/// `enum_tag(reserved_tags = [3, 7])` lists tags of removed variants like protobuf `reserved`.
+/// Reusing a reserved tag warns, or fails to compile with `enum_tag(deny_reserved)`.
+///
+/// ```compile_fail
+/// use roset::EnumTag;
+///
+/// #[derive(EnumTag)]
+/// #[enum_tag(reserved_tags = [2], deny_reserved)]
+/// enum Message {
+///     #[enum_tag(1)]
+///     Close,
+///     // error: tag 2 is reserved
+///     #[enum_tag(2)]
+///     Ping,
+/// }
+/// ```
+///
+/// Tags may be const expressions like `enum_tag(BASE + 1)`, these are checked by const assertions
+/// when the enum compiles, uniqueness, `contiguous` and `deny_reserved` included, but reusing a
+/// reserved tag does not warn.
+///
+/// ```compile_fail
+/// use roset::EnumTag;
+///
+/// const BASE: u16 = 10;
+///
+/// #[derive(EnumTag)]
+/// enum Message {
+///     #[enum_tag(BASE + 1)]
+///     Close,
+///     // error: value of Ping already used by an earlier variant
+///     #[enum_tag(11)]
+///     Ping,
+/// }
+/// ```
+#[proc_macro_derive(EnumTag, attributes(enum_tag, roset))]
+pub fn enum_tag(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs) = (input.vis.clone(), input.attrs.clone());
+    let (id, data) = assert_enum("EnumTag", input);
+    let mut handler = EnumTag::new(vis, id.clone(), attrs.clone(), data);
+    match handler.parse_attributes() {
+        Ok(_) => finish("EnumTag", &id, &attrs, handler.write_output()),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Implement `unwrap_{variant}` for every tuple variant in `enum`, returning the inner value
+/// and panicking with both variant names on mismatch, like `Option::unwrap`
+///
+/// ```
+/// use roset::EnumUnwrap;
+///
+/// #[derive(EnumUnwrap)]
+/// enum Number {
+///     Integer(i32),
+///     Pair(i32, i32),
+///     Nothing,
+/// }
+///
+/// assert_eq!(Number::Integer(1).unwrap_integer(), 1);
+/// assert_eq!(Number::Pair(1, 2).unwrap_pair(), (1, 2));
+/// ```
+///
+/// `map_{variant}` transforms the inner value of a tuple variant, a tuple for several fields, and
+/// returns other variants unchanged. `map_payloads` maps any variant when all of them are units
+/// or tuples of one field of the same type.
+///
+/// ```
+/// use roset::EnumUnwrap;
+///
+/// #[derive(Debug, PartialEq, EnumUnwrap)]
+/// enum Number {
+///     Integer(i32),
+///     Natural(i32),
+///     Nothing,
+/// }
+///
+/// assert_eq!(Number::Integer(1).map_integer(|v| v + 1), Number::Integer(2));
+/// assert_eq!(Number::Natural(1).map_integer(|v| v + 1), Number::Natural(1));
+/// assert_eq!(Number::Natural(2).map_payloads(|v| v * 2), Number::Natural(4));
+/// assert_eq!(Number::Nothing.map_payloads(|v| v * 2), Number::Nothing);
+/// ```
+///
+/// ```should_panic
+/// # use roset::EnumUnwrap;
+/// # #[derive(EnumUnwrap)]
+/// # enum Number {
+/// #     Integer(i32),
+/// #     Nothing,
+/// # }
+/// // panics: called `Number::unwrap_integer()` on a `Number::Nothing` value
+/// Number::Nothing.unwrap_integer();
+/// ```
+#[proc_macro_derive(EnumUnwrap, attributes(roset))]
+pub fn enum_unwrap(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs, generics) = (
+        input.vis.clone(),
+        input.attrs.clone(),
+        input.generics.clone(),
+    );
+    let (id, data) = assert_enum("EnumUnwrap", input);
+    finish(
+        "EnumUnwrap",
+        &id,
+        &attrs,
+        EnumUnwrap::new(vis, id.clone(), generics, data).write_output(),
+    )
+}
+
+/// Implement `payload_size` and `MAX_PAYLOAD_SIZE` for `enum`, the sum of `size_of` of the
+/// variant fields without padding or discriminant, for protocol framing and buffer sizing
+///
+/// ```
+/// use roset::EnumPayloadSize;
+///
+/// #[derive(EnumPayloadSize)]
+/// enum Frame {
+///     Ping,
+///     Ack(u32),
+///     Data { len: u16, body: [u8; 8] },
+/// }
+///
+/// assert_eq!(Frame::Ping.payload_size(), 0);
+/// assert_eq!(Frame::Ack(1).payload_size(), 4);
+/// assert_eq!(Frame::MAX_PAYLOAD_SIZE, 10);
+/// ```
+#[proc_macro_derive(EnumPayloadSize, attributes(roset))]
+pub fn enum_payload_size(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs, generics) = (
+        input.vis.clone(),
+        input.attrs.clone(),
+        input.generics.clone(),
+    );
+    let (id, data) = assert_enum("EnumPayloadSize", input);
+    finish(
+        "EnumPayloadSize",
+        &id,
+        &attrs,
+        EnumPayloadSize::new(vis, id.clone(), generics, data).write_output(),
+    )
+}
+
+/// Implement `arity` and the `ARITIES`, `FIELD_NAMES` tables for `enum`, describing the fields of
+/// every variant for code generators and dispatchers reflecting over the enum
+///
+/// ```
+/// use roset::EnumArity;
+///
+/// #[derive(EnumArity)]
+/// enum Request {
+///     Ping,
+///     Get(String),
+///     Put { key: String, value: Vec<u8> },
+/// }
+///
+/// assert_eq!(Request::Get("a".into()).arity(), 1);
+/// assert_eq!(Request::ARITIES, [0, 1, 2]);
+/// assert_eq!(Request::FIELD_NAMES[1], ["0"]);
+/// assert_eq!(Request::FIELD_NAMES[2], ["key", "value"]);
+/// ```
+#[proc_macro_derive(EnumArity, attributes(roset))]
+pub fn enum_arity(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs, generics) = (
+        input.vis.clone(),
+        input.attrs.clone(),
+        input.generics.clone(),
+    );
+    let (id, data) = assert_enum("EnumArity", input);
+    finish(
+        "EnumArity",
+        &id,
+        &attrs,
+        EnumArity::new(vis, id.clone(), generics, data).write_output(),
+    )
+}
+
+/// Implement a `{Enum}Map<V>` keyed by variants of unit-only `enum`, stored in a fixed array
+/// without hashing
+///
+/// ```
+/// use roset::EnumMap;
+///
+/// #[derive(PartialEq, Debug, Clone, Copy, EnumMap)]
+/// enum Weekday {
+///     Monday,
+///     Tuesday,
+///     Wednesday,
+/// }
+///
+/// let mut hours = WeekdayMap::new();
+/// assert_eq!(hours.insert(Weekday::Tuesday, 8), None);
+/// assert_eq!(hours.insert(Weekday::Monday, 6), None);
+/// assert_eq!(hours.get(Weekday::Monday), Some(&6));
+/// assert_eq!(hours.get(Weekday::Wednesday), None);
+///
+/// let items: Vec<_> = hours.iter().collect();
+/// assert_eq!(items, vec![(Weekday::Monday, &6), (Weekday::Tuesday, &8)]);
+///
+/// let names = WeekdayMap::from_fn(|day| format!("{:?}", day));
+/// assert_eq!(names.get(Weekday::Wednesday).unwrap(), "Wednesday");
+/// ```
+#[proc_macro_derive(EnumMap, attributes(roset))]
+pub fn enum_map(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs) = (input.vis.clone(), input.attrs.clone());
+    let (id, data) = assert_enum("EnumMap", input);
+    let mut handler = EnumMap::new(vis, id.clone(), attrs.clone(), data);
+    match handler.parse_attributes() {
+        Ok(_) => finish("EnumMap", &id, &attrs, handler.write_output()),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Implement `value` and `from_value` for unit-only `enum` with typed constants declared by
+/// `enum_value(u16 = 443)`, independent of the discriminants
+///
+/// The value type must be an integer type and the same for all variants, values could be written
+/// in decimal, hex `0x1f` or binary `0b11` literals, and `to_hex_str`, `from_hex_str` helpers are
+/// generated as well. Values may also be const expressions like `enum_value(u16 = BASE + 1)`,
+/// their uniqueness is then checked by a const assertion when the enum compiles.
+///
+/// ```
+/// use roset::EnumValue;
+///
+/// #[derive(PartialEq, Debug, EnumValue)]
+/// enum Service {
+///     #[enum_value(u16 = 443)]
+///     Https,
+///     #[enum_value(u16 = 22)]
+///     Ssh,
+///     #[enum_value(u16 = 0x50)]
+///     Http,
+/// }
+///
+/// assert_eq!(Service::Https.value(), 443);
+/// assert_eq!(Service::from_value(80), Some(Service::Http));
+/// assert_eq!(Service::from_value(21), None);
+///
+/// #[derive(PartialEq, Debug, EnumValue)]
+/// enum Register {
+///     #[enum_value(u8 = 0b0001_0000)]
+///     Control,
+///     #[enum_value(u8 = 0x2a)]
+///     Status,
+/// }
+///
+/// assert_eq!(Register::Control.to_hex_str(), "0x10");
+/// assert_eq!(Register::from_hex_str("0x2A"), Some(Register::Status));
+/// assert_eq!(Register::from_hex_str("10"), Some(Register::Control));
+/// assert_eq!(Register::from_hex_str("zz"), None);
+/// ```
+///
+/// Signed value types take negative values, written in hex with a sign like `-0x1`
+///
+/// ```
+/// use roset::EnumValue;
+///
+/// #[derive(PartialEq, Debug, EnumValue)]
+/// enum Level {
+///     #[enum_value(i8 = -1)]
+///     Debug,
+///     #[enum_value(i8 = 0)]
+///     Info,
+/// }
+///
+/// assert_eq!(Level::Debug.value(), -1);
+/// assert_eq!(Level::from_value(-1), Some(Level::Debug));
+/// assert_eq!(Level::Debug.to_hex_str(), "-0x1");
+/// assert_eq!(Level::from_hex_str("-0x1"), Some(Level::Debug));
+/// assert_eq!(Level::from_hex_str("0xff"), None);
+/// ```
+#[proc_macro_derive(EnumValue, attributes(enum_value, roset))]
+pub fn enum_value(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs) = (input.vis.clone(), input.attrs.clone());
+    let (id, data) = assert_enum("EnumValue", input);
+    let mut handler = EnumValue::new(vis, id.clone(), data);
+    match handler.parse_attributes() {
+        Ok(_) => finish("EnumValue", &id, &attrs, handler.write_output()),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Implement `MIN`, `MAX`, `in_range` and `from_clamped` for unit-only `enum` with numeric values,
+/// declared by `enum_value(u8 = 10)` or the discriminants of an integer repr, for quantized
+/// settings like log levels
+///
+/// `from_clamped` returns the variant with the nearest value, the lower one on a tie
+///
+/// ```
+/// use roset::{EnumRange, EnumValue};
+///
+/// #[derive(PartialEq, Debug, EnumRange)]
+/// #[repr(u8)]
+/// enum Level {
+///     Error = 1,
+///     Warn = 2,
+///     Info = 3,
+///     Trace = 5,
+/// }
+///
+/// assert_eq!((Level::MIN, Level::MAX), (1, 5));
+/// assert!(Level::in_range(4));
+/// assert!(!Level::in_range(0));
+/// assert_eq!(Level::from_clamped(0), Level::Error);
+/// assert_eq!(Level::from_clamped(4), Level::Info);
+/// assert_eq!(Level::from_clamped(200), Level::Trace);
+///
+/// #[derive(PartialEq, Debug, EnumValue, EnumRange)]
+/// enum Quality {
+///     #[enum_value(u32 = 720)]
+///     Hd,
+///     #[enum_value(u32 = 480)]
+///     Sd,
+///     #[enum_value(u32 = 1080)]
+///     FullHd,
+/// }
+///
+/// assert_eq!((Quality::MIN, Quality::MAX), (480, 1080));
+/// assert_eq!(Quality::from_clamped(800), Quality::Hd);
+/// assert_eq!(Quality::from_clamped(1000), Quality::FullHd);
+/// ```
+///
+/// Values may be negative, const expressions accepted by `EnumValue` are rejected since the
+/// range is computed by the macro
+///
+/// ```
+/// use roset::{EnumRange, EnumValue};
+///
+/// #[derive(PartialEq, Debug, EnumValue, EnumRange)]
+/// enum Verbosity {
+///     #[enum_value(i8 = -2)]
+///     Silent,
+///     #[enum_value(i8 = -1)]
+///     Quiet,
+///     #[enum_value(i8 = 1)]
+///     Verbose,
+/// }
+///
+/// assert_eq!((Verbosity::MIN, Verbosity::MAX), (-2, 1));
+/// assert_eq!(Verbosity::from_clamped(-100), Verbosity::Silent);
+/// assert_eq!(Verbosity::from_clamped(0), Verbosity::Quiet);
+/// ```
+///
+/// ```compile_fail
+/// use roset::{EnumRange, EnumValue};
+///
+/// const HI: i8 = 9;
+///
+/// #[derive(EnumValue, EnumRange)]
+/// enum Verbosity {
+///     #[enum_value(i8 = 0)]
+///     Quiet,
+///     // error: EnumRange needs an integer literal value like `-1`
+///     #[enum_value(i8 = HI)]
+///     Loud,
+/// }
+/// ```
+#[proc_macro_derive(EnumRange, attributes(enum_value, roset))]
+pub fn enum_range(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs) = (input.vis.clone(), input.attrs.clone());
+    let (id, data) = assert_enum("EnumRange", input);
+    let mut handler = EnumRange::new(vis, id.clone(), attrs.clone(), data);
+    match handler.parse_attributes() {
+        Ok(_) => finish("EnumRange", &id, &attrs, handler.write_output()),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Implement `PartialOrd` and `Ord` for `enum` by variant declaration order, or by weights
+/// declared with `enum_order(10)`, ignoring payloads, for severity and priority enums
+///
+/// - a variant without weight takes the weight of the previous variant plus one, like
+///   discriminants, starting from 0
+/// - `Ord` requires `Eq`, `enum_order(eq)` on the enum implements `PartialEq` and `Eq` comparing
+///   weights as well, for payloads which are not comparable
+/// - without `enum_order(eq)`, weights must be unique and variants unit, so `Ord` agrees with a
+///   derived `PartialEq`, payload variants and shared weights require `enum_order(eq)`
+///
+/// ```
+/// use roset::EnumOrder;
+///
+/// #[derive(Debug, EnumOrder)]
+/// #[enum_order(eq)]
+/// enum Severity {
+///     Debug(String),
+///     #[enum_order(10)]
+///     Warning { line: usize },
+///     Error(Box<dyn std::error::Error>),
+///     #[enum_order(5)]
+///     Notice,
+/// }
+///
+/// assert!(Severity::Error("disk".into()) > Severity::Warning { line: 1 });
+/// assert!(Severity::Notice < Severity::Warning { line: 2 });
+/// assert!(Severity::Debug("a".into()) < Severity::Notice);
+/// assert_eq!(Severity::Warning { line: 1 }, Severity::Warning { line: 2 });
+/// assert_eq!(Severity::Error("io".into()).order_weight(), 11);
+/// ```
+///
+/// ```compile_fail
+/// use roset::EnumOrder;
+///
+/// #[derive(PartialEq, Eq, EnumOrder)]
+/// enum Priority {
+///     #[enum_order(1)]
+///     Low,
+///     // error: enum_order weight 1 already used by Low
+///     #[enum_order(1)]
+///     Minor,
+/// }
+/// ```
+#[proc_macro_derive(EnumOrder, attributes(enum_order, roset))]
+pub fn enum_order(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs, generics) = (
+        input.vis.clone(),
+        input.attrs.clone(),
+        input.generics.clone(),
+    );
+    let (id, data) = assert_enum("EnumOrder", input);
+    let mut handler = EnumOrder::new(vis, id.clone(), attrs.clone(), generics, data);
+    match handler.parse_attributes() {
+        Ok(_) => finish("EnumOrder", &id, &attrs, handler.write_output()),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Implement `is_one_of` for unit-only `enum` and a `{Enum}StaticSet` bitmask, for permission and
+/// role checks on hot paths
+///
+/// - `Role::Admin.is_one_of(&[Role::Admin, Role::Owner])` folds into a single mask test
+/// - `{Enum}StaticSet::of(&[..])` and the `{enum}_set!(A, B)` macro build a set in `const` items,
+///   the macro is usable below the enum in its module and child modules, it names the enum and
+///   `{Enum}StaticSet` unqualified so both must be in scope where it is called
+/// - the bitmask is the smallest unsigned integer with a bit for every variant, up to 128
+///
+/// ```
+/// use roset::EnumIntoStaticSet;
+///
+/// #[derive(EnumIntoStaticSet)]
+/// enum Role {
+///     Guest,
+///     Member,
+///     Admin,
+///     Owner,
+/// }
+///
+/// const STAFF: RoleStaticSet = role_set!(Admin, Owner);
+///
+/// assert!(Role::Admin.is_in(STAFF));
+/// assert!(!Role::Guest.is_in(STAFF));
+/// assert!(Role::Member.is_one_of(&[Role::Guest, Role::Member]));
+/// assert_eq!(STAFF.union(RoleStaticSet::of(&[Role::Guest])).bits(), 0b1101);
+/// ```
+#[proc_macro_derive(EnumIntoStaticSet, attributes(roset))]
+pub fn enum_into_static_set(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs) = (input.vis.clone(), input.attrs.clone());
+    let (id, data) = assert_enum("EnumIntoStaticSet", input);
+    let mut handler = EnumIntoStaticSet::new(vis, id.clone(), attrs.clone(), data);
+    match handler.parse_attributes() {
+        Ok(_) => finish("EnumIntoStaticSet", &id, &attrs, handler.write_output()),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Generate `{Enum}Ref<'a>`, the borrowed form of `enum` for zero-copy parsing APIs, with
+/// `as_ref` and `to_owned` converting between both
+///
+/// - `String` payloads are borrowed as `&'a str`, `Vec<T>` as `&'a [T]` and other types as `&'a T`
+/// - `to_owned` clones the payloads with `ToOwned`, `From` is implemented both ways
+/// - `{Enum}Ref` derives `Clone` and `Copy`, `#[enum_ref(derive(Debug, PartialEq))]` adds others
+///
+/// ```
+/// use roset::EnumRef;
+///
+/// #[derive(Debug, PartialEq, EnumRef)]
+/// #[enum_ref(derive(Debug, PartialEq))]
+/// enum Token {
+///     Word(String),
+///     Bytes(Vec<u8>),
+///     Number { value: i64 },
+///     End,
+/// }
+///
+/// let token = Token::Word("hello".to_string());
+/// assert_eq!(token.as_ref(), TokenRef::Word("hello"));
+/// assert_eq!(TokenRef::Bytes(b"\x01").to_owned(), Token::Bytes(vec![1]));
+/// assert_eq!(TokenRef::Number { value: &1 }.to_owned(), Token::Number { value: 1 });
+///
+/// fn parse(input: &str) -> TokenRef<'_> {
+///     TokenRef::Word(input.trim())
+/// }
+/// assert_eq!(Token::from(parse(" hi ")), Token::Word("hi".to_string()));
+/// ```
+#[proc_macro_derive(EnumRef, attributes(enum_ref, roset))]
+pub fn enum_ref(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs, generics) = (
+        input.vis.clone(),
+        input.attrs.clone(),
+        input.generics.clone(),
+    );
+    let (id, data) = assert_enum("EnumRef", input);
+    let mut handler = EnumRef::new(vis, id.clone(), attrs.clone(), generics, data);
+    match handler.parse_attributes() {
+        Ok(_) => finish("EnumRef", &id, &attrs, handler.write_output()),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Implement `Debug` for `enum` writing the `str` of unit variants through `StrRepr`, which
+/// `derive(EnumFrom)` implements, while payload variants are formatted like `derive(Debug)`
+///
+/// ```
+/// use roset::{EnumDebugStr, EnumFrom};
+///
+/// #[derive(EnumFrom, EnumDebugStr)]
+/// enum Token {
+///     #[enum_from(str = "+")]
+///     Plus,
+///     #[enum_from(str = "ident")]
+///     Ident(String),
+///     #[enum_from(str = "number")]
+///     Number { value: i64 },
+/// }
+///
+/// assert_eq!(format!("{:?}", Token::Plus), "+");
+/// assert_eq!(format!("{:?}", Token::Ident("x".into())), r#"Ident("x")"#);
+/// assert_eq!(format!("{:?}", Token::Number { value: 1 }), "Number { value: 1 }");
+/// assert_eq!(format!("{:?}", vec![Token::Plus]), "[+]");
+/// ```
+#[proc_macro_derive(EnumDebugStr, attributes(roset))]
+pub fn enum_debug_str(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (attrs, generics) = (input.attrs.clone(), input.generics.clone());
+    let (id, data) = assert_enum("EnumDebugStr", input);
+    finish(
+        "EnumDebugStr",
+        &id,
+        &attrs,
+        EnumDebugStr::new(id.clone(), generics, data).write_output(),
+    )
+}
+
+/// Implement `parse_list` and `join` for lists of variants like `"red, green"` in CLIs and
+/// config files, through `FromStr` and `StrRepr` which `derive(EnumFrom)` implements
+///
+/// ```
+/// use roset::{EnumFrom, EnumFromIterator};
+///
+/// #[derive(Debug, PartialEq, EnumFrom, EnumFromIterator)]
+/// enum Color {
+///     #[enum_from(str = "red")]
+///     Red,
+///     #[enum_from(str = "green")]
+///     Green,
+/// }
+///
+/// let colors = Color::parse_list("red, green,red", ',').unwrap();
+/// assert_eq!(colors, [Color::Red, Color::Green, Color::Red]);
+/// assert_eq!(Color::join(&colors, ","), "red,green,red");
+/// assert_eq!(Color::parse_list("", ','), Ok(vec![]));
+/// assert!(Color::parse_list("red,blue", ',').is_err());
+/// ```
+#[proc_macro_derive(EnumFromIterator, attributes(roset))]
+pub fn enum_from_iterator(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs, generics) = (
+        input.vis.clone(),
+        input.attrs.clone(),
+        input.generics.clone(),
+    );
+    let (id, _) = assert_enum("EnumFromIterator", input);
+    finish(
+        "EnumFromIterator",
+        &id,
+        &attrs,
+        EnumFromIterator::new(vis, id.clone(), generics).write_output(),
+    )
+}
+
+/// Implement `From` an older version of `enum` declared by `#[migrate(from = "v1::Status")]`,
+/// mapping variants of the same name, for persisted enums evolving over time
+///
+/// - `renamed(Active = "Enabled")` maps the old `Active` to the new `Enabled`
+/// - `added(Paused)` marks new variants without counterpart in the old enum
+/// - `removed(Legacy)` marks old variants without counterpart, `TryFrom` is implemented instead,
+///   failing with `{Enum}MigrateError`
+/// - payload fields are converted with `Into`, the old variant has the fields of the new one
+/// - `#[migrate(..)]` may be repeated, once for every old version
+///
+/// ```
+/// use roset::EnumMigrate;
+///
+/// mod v1 {
+///     pub enum Status {
+///         Active,
+///         Disabled,
+///         Legacy,
+///         Retry(u8),
+///     }
+/// }
+///
+/// #[derive(PartialEq, Debug, EnumMigrate)]
+/// #[migrate(
+///     from = "v1::Status",
+///     renamed(Active = "Enabled"),
+///     removed(Legacy),
+///     added(Paused)
+/// )]
+/// enum Status {
+///     Enabled,
+///     Disabled,
+///     Paused,
+///     Retry(u32),
+/// }
+///
+/// assert_eq!(Status::try_from(v1::Status::Active), Ok(Status::Enabled));
+/// assert_eq!(Status::try_from(v1::Status::Retry(3)), Ok(Status::Retry(3)));
+/// let err = Status::try_from(v1::Status::Legacy).unwrap_err();
+/// assert_eq!(err.to_string(), "variant v1::Status::Legacy was removed from Status");
+/// ```
+#[proc_macro_derive(EnumMigrate, attributes(migrate, roset))]
+pub fn enum_migrate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs) = (input.vis.clone(), input.attrs.clone());
+    let (id, data) = assert_enum("EnumMigrate", input);
+    let mut handler = EnumMigrate::new(vis, id.clone(), attrs.clone(), data);
+    match handler.parse_attributes() {
+        Ok(_) => finish("EnumMigrate", &id, &attrs, handler.write_output()),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Implement `code`, `message`, `from_code` and `Display` for unit-only `enum` declaring
+/// `enum_code(code = 404, message = "Not Found")` on every variant
+///
+/// - `message` defaults to the variant name
+/// - `enum_code(code_type = "u32")` on the enum changes the code type, `u16` by default
+///
+/// ```
+/// use roset::EnumCode;
+///
+/// #[derive(PartialEq, Debug, EnumCode)]
+/// enum Status {
+///     #[enum_code(code = 200, message = "OK")]
+///     Ok,
+///     #[enum_code(code = 404, message = "Not Found")]
+///     NotFound,
+///     #[enum_code(code = 418)]
+///     Teapot,
+/// }
+///
+/// assert_eq!(Status::NotFound.code(), 404);
+/// assert_eq!(Status::NotFound.message(), "Not Found");
+/// assert_eq!(Status::from_code(200), Some(Status::Ok));
+/// assert_eq!(Status::from_code(500), None);
+/// assert_eq!(Status::Teapot.to_string(), "Teapot");
+/// ```
+#[proc_macro_derive(EnumCode, attributes(enum_code, roset))]
+pub fn enum_code(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (vis, attrs) = (input.vis.clone(), input.attrs.clone());
+    let (id, data) = assert_enum("EnumCode", input);
+    let mut handler = EnumCode::new(vis, id.clone(), attrs.clone(), data);
+    match handler.parse_attributes() {
+        Ok(_) => finish("EnumCode", &id, &attrs, handler.write_output()),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Compiler warning at the span, as stable proc macros can not warn, by using a deprecated item
+fn warning(span: proc_macro2::Span, name: &str, note: &str) -> proc_macro2::TokenStream {
+    let name = Ident::new(name, span);
+    quote::quote_spanned! {span=>
+        const _: () = {
+            #[deprecated(note = #note)]
+            const #name: () = ();
+            #name
+        };
+    }
+}
+
+/// Compile time check that values given by const expressions are unique, failing with the name
+/// of the variant repeating an earlier value, `strs` compares `&str` values by bytes
+fn const_assert_unique<T: quote::ToTokens>(
+    title: &str,
+    value_type: &proc_macro2::TokenStream,
+    values: &[(&Ident, &T)],
+    strs: bool,
+) -> proc_macro2::TokenStream {
+    let count = values.len();
+    let exprs = values.iter().map(|(_, expr)| expr);
+    let messages = values.iter().map(|(var, _)| {
+        format!(
+            "{}: value of {} already used by an earlier variant",
+            title, var
+        )
+    });
+    let eq = match strs {
+        true => quote::quote! { ::roset::__private::str_eq(VALUES[i], VALUES[j]) },
+        false => quote::quote! { VALUES[i] == VALUES[j] },
+    };
+    quote::quote! {
+        const _: () = {
+            const VALUES: [#value_type; #count] = [#(#exprs),*];
+            const MESSAGES: [&str; #count] = [#(#messages),*];
+            let mut i = 0;
+            while i < #count {
+                let mut j = 0;
+                while j < i {
+                    if #eq {
+                        panic!("{}", MESSAGES[i]);
+                    }
+                    j += 1;
+                }
+                i += 1;
+            }
+        };
+    }
+}
+
+/// Final output of a derive: impls are grouped in a `const _` block marked with the derive name,
+/// items stay in generation order, the `#[roset(cfg = "..")]` predicate is applied to every item
+/// and the code is dumped to a file with `#[roset(dump)]` or the `ROSET_DUMP_DIR` env var,
+/// `#[roset(report)]` warns with a summary of the items and `#[roset(vis = "..")]` changes the
+/// visibility of public methods and consts in inherent impls
+fn finish(
+    derive: &str,
+    enum_name: &Ident,
+    attrs: &[Attribute],
+    output: proc_macro2::TokenStream,
+) -> TokenStream {
+    let RosetOptions {
+        cfg,
+        dump,
+        report,
+        vis,
+        ..
+    } = match RosetOptions::parse(attrs) {
+        Ok(options) => options,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let mut items = syn::parse2::<syn::File>(output)
+        .expect("generated code is a list of items")
+        .items;
+    if let Some(vis) = vis {
+        for item in items.iter_mut() {
+            if let syn::Item::Impl(item @ syn::ItemImpl { trait_: None, .. }) = item {
+                set_method_vis(item, &vis);
+            }
+        }
+    }
+    let (impls, mut items): (Vec<_>, Vec<_>) = items.into_iter().partition(|item| match item {
+        syn::Item::Impl(_) => true,
+        syn::Item::Const(item) => item.ident == "_",
+        _ => false,
+    });
+    let summary = report.then(|| {
+        let described: Vec<_> = items
+            .iter()
+            .chain(impls.iter())
+            .flat_map(describe_item)
+            .collect();
+        format!(
+            "roset report, #[derive({})] for {} generates: {}",
+            derive,
+            enum_name,
+            described.join("; ")
+        )
+    });
+    if !impls.is_empty() {
+        let marker = format!("roset: #[derive({})] for `{}`", derive, enum_name);
+        items.push(syn::parse_quote! {
+            #[doc = #marker]
+            const _: () = { #(#impls)* };
+        });
+    }
+    if let Some(cfg) = cfg {
+        for item in items.iter_mut() {
+            push_item_attr(item, syn::parse_quote!(#[cfg(#cfg)]));
+        }
+    }
+
+    let dir = match std::env::var_os("ROSET_DUMP_DIR") {
+        Some(dir) => Some(std::path::PathBuf::from(dir)),
+        None if dump => std::env::var_os("CARGO_MANIFEST_DIR")
+            .map(|dir| std::path::Path::new(&dir).join("target").join("roset")),
+        None => None,
+    };
+    if let Some(dir) = dir {
+        let path = dir.join(format!("{}.{}.rs", enum_name, derive));
+        let code: String = items
+            .iter()
+            .map(|item| format!("{}\n", quote::quote!(#item)))
+            .collect();
+        let written = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, code));
+        if let Err(err) = written {
+            let reason = format!("roset: can not dump to {}: {}", path.display(), err);
+            return syn::Error::new(enum_name.span(), reason)
+                .to_compile_error()
+                .into();
+        }
+    }
+    // reported even when the cfg predicate disables the items
+    let report = summary.map(|note| warning(enum_name.span(), "ROSET_REPORT", &note));
+    quote::quote!(#(#items)* #report).into()
+}
+
+/// Short description of a generated item, inner items for blocks of `const _`
+fn describe_item(item: &syn::Item) -> Vec<String> {
+    use syn::Item;
+    let tokens = |tokens: &dyn quote::ToTokens| {
+        let text = tokens.to_token_stream().to_string();
+        [
+            (" < ", "<"),
+            ("< ", "<"),
+            (" >", ">"),
+            (" :: ", "::"),
+            (":: ", "::"),
+            (" ,", ","),
+            ("& ", "&"),
+        ]
+        .iter()
+        .fold(text, |text, (from, to)| text.replace(from, to))
+    };
+    match item {
+        Item::Struct(item) => vec![format!("struct {}", item.ident)],
+        Item::Enum(item) => vec![format!("enum {}", item.ident)],
+        Item::Mod(item) => vec![format!("mod {}", item.ident)],
+        Item::Trait(item) => vec![format!("trait {}", item.ident)],
+        Item::Macro(item) => match &item.ident {
+            Some(ident) => vec![format!("macro {}!", ident)],
+            None => vec![],
+        },
+        Item::Const(item) if item.ident == "_" => match &*item.expr {
+            syn::Expr::Block(block) => block
+                .block
+                .stmts
+                .iter()
+                .filter_map(|stmt| match stmt {
+                    syn::Stmt::Item(item @ Item::Impl(_)) => Some(describe_item(item)),
+                    _ => None,
+                })
+                .flatten()
+                .collect(),
+            _ => vec![],
+        },
+        Item::Const(item) => vec![format!("const {}", item.ident)],
+        Item::Impl(item) => match &item.trait_ {
+            Some((_, path, _)) => {
+                let name = path.segments.last().unwrap();
+                vec![format!(
+                    "impl {} for {}",
+                    tokens(name),
+                    tokens(&item.self_ty)
+                )]
+            }
+            None => {
+                let members: Vec<_> = item
+                    .items
+                    .iter()
+                    .filter_map(|member| match member {
+                        syn::ImplItem::Method(method) => Some(method.sig.ident.to_string()),
+                        syn::ImplItem::Const(constant) => Some(constant.ident.to_string()),
+                        _ => None,
+                    })
+                    .collect();
+                vec![format!(
+                    "impl {} {{ {} }}",
+                    tokens(&item.self_ty),
+                    members.join(", ")
+                )]
+            }
+        },
+        _ => vec![],
+    }
+}
+
+/// Private helpers keep their visibility
+fn set_method_vis(item: &mut syn::ItemImpl, vis: &syn::Visibility) {
+    for member in item.items.iter_mut() {
+        let member_vis = match member {
+            syn::ImplItem::Method(method) => &mut method.vis,
+            syn::ImplItem::Const(constant) => &mut constant.vis,
+            _ => continue,
+        };
+        if !matches!(member_vis, syn::Visibility::Inherited) {
+            *member_vis = vis.clone();
+        }
+    }
+}
+
+fn push_item_attr(item: &mut syn::Item, attr: Attribute) {
+    use syn::Item;
+    let attrs = match item {
+        Item::Const(item) => &mut item.attrs,
+        Item::Enum(item) => &mut item.attrs,
+        Item::Fn(item) => &mut item.attrs,
+        Item::Impl(item) => &mut item.attrs,
+        Item::Mod(item) => &mut item.attrs,
+        Item::Static(item) => &mut item.attrs,
+        Item::Struct(item) => &mut item.attrs,
+        Item::Trait(item) => &mut item.attrs,
+        Item::Type(item) => &mut item.attrs,
+        Item::Union(item) => &mut item.attrs,
+        Item::Use(item) => &mut item.attrs,
+        Item::Macro(item) => &mut item.attrs,
+        _ => panic!("roset: unexpected generated item"),
+    };
+    attrs.insert(0, attr);
+}
+
+fn assert_enum(name: &str, input: DeriveInput) -> (Ident, DataEnum) {
+    let ident = input.ident.clone();
+    match input.data {
+        Data::Enum(data) => (ident, data),
+        _ => panic!("{} must be an enum to use {}", &ident, name),
+    }
+}
+
+fn get_discriminants(enum_name: &Ident, data: &DataEnum) -> Vec<(Ident, i128)> {
+    let mut next = 0;
+    data.variants
+        .iter()
+        .map(|var| {
+            if let Some((_, expr)) = &var.discriminant {
+                next = eval_discriminant(expr).unwrap_or_else(|| {
+                    panic!(
+                        "{}::{}: discriminant must be an integer literal",
+                        enum_name, var.ident
+                    )
+                });
+            }
+
+            next += 1;
+            (var.ident.clone(), next - 1)
+        })
+        .collect()
+}
+
+fn eval_discriminant(expr: &Expr) -> Option<i128> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.base10_parse().ok(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => eval_discriminant(expr).map(|value| -value),
+        Expr::Paren(ExprParen { expr, .. }) => eval_discriminant(expr),
+        Expr::Group(group) => eval_discriminant(&group.expr),
+        _ => None,
+    }
+}
+
+fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::new();
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() {
+            // split before a word, keep acronym like `HTTPCode` as `http_code`
+            let prev_lower = i > 0 && !chars[i - 1].is_uppercase() && chars[i - 1] != '_';
+            let next_lower = i > 0 && chars.get(i + 1).is_some_and(|ch| ch.is_lowercase());
+            if prev_lower || (next_lower && chars[i - 1].is_uppercase()) {
+                snake.push('_');
+            }
+            snake.extend(ch.to_lowercase());
+        } else {
+            snake.push(ch);
+        }
+    }
+    snake
+}
+
+/// Cases accepted by `rename_all`, named like serde does
+const RENAME_CASES: &[&str] = &[
+    "lowercase",
+    "UPPERCASE",
+    "PascalCase",
+    "camelCase",
+    "snake_case",
+    "SCREAMING_SNAKE_CASE",
+    "kebab-case",
+    "SCREAMING-KEBAB-CASE",
+];
+
+/// Rename a `PascalCase` variant name, `None` for a case not in `RENAME_CASES`
+fn rename_case(name: &str, case: &str) -> Option<String> {
+    let snake = to_snake_case(name);
+    let words = snake.split('_').filter(|word| !word.is_empty());
+    let capitalize = |word: &str| {
+        let mut chars = word.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+            .unwrap_or_default()
+    };
+
+    let renamed = match case {
+        "lowercase" => name.to_lowercase(),
+        "UPPERCASE" => name.to_uppercase(),
+        "PascalCase" => words.map(capitalize).collect(),
+        "camelCase" => {
+            let pascal: String = words.map(capitalize).collect();
+            let mut chars = pascal.chars();
+            chars
+                .next()
+                .map(|first| first.to_lowercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+        "snake_case" => words.collect::<Vec<_>>().join("_"),
+        "SCREAMING_SNAKE_CASE" => words.collect::<Vec<_>>().join("_").to_uppercase(),
+        "kebab-case" => words.collect::<Vec<_>>().join("-"),
+        "SCREAMING-KEBAB-CASE" => words.collect::<Vec<_>>().join("-").to_uppercase(),
+        _ => return None,
+    };
+    Some(renamed)
+}
+
+/// Whether the type is written as `PhantomData<..>`, with or without its path
+fn is_phantom(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "PhantomData"),
+        _ => false,
+    }
+}
+
+/// Key comparing types as written, ignoring spacing, lifetimes and a leading `::`
+fn type_key<T: quote::ToTokens>(ty: &T) -> String {
+    fn push_tokens(tokens: proc_macro2::TokenStream, key: &mut String) {
+        let mut tokens = tokens.into_iter();
+        while let Some(token) = tokens.next() {
+            match token {
+                TokenTree::Punct(punct) if punct.as_char() == '\'' => {
+                    tokens.next();
+                }
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::Brace => ("{", "}"),
+                        Delimiter::None => ("", ""),
+                    };
+                    key.push_str(open);
+                    push_tokens(group.stream(), key);
+                    key.push_str(close);
+                }
+                // keep words apart, like `dyn Fn`
+                TokenTree::Ident(ident) => {
+                    if key.ends_with(|ch: char| ch.is_alphanumeric() || ch == '_') {
+                        key.push(' ');
+                    }
+                    key.push_str(&ident.to_string());
+                }
+                token => key.push_str(&token.to_string()),
+            }
+        }
+    }
+
+    let mut key = String::new();
+    push_tokens(quote::quote!(#ty), &mut key);
+    key.replace("&::", "&").trim_start_matches("::").to_string()
+}
+
+fn get_doc_attrs(attrs: &[Attribute]) -> Vec<&Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .collect()
+}
+
+fn get_deprecated(attrs: &[Attribute]) -> Option<&Attribute> {
+    attrs.iter().find(|attr| attr.path.is_ident("deprecated"))
+}
+
+fn get_wrapped_unnamed(
+    macro_name: &str,
+    enum_name: &Ident,
+    fields: Fields,
+) -> Punctuated<Field, Comma> {
+    let err = format!("{}: can not use {}", enum_name, macro_name);
+    match fields {
+        Fields::Unnamed(field) => field.unnamed,
+        Fields::Unit => panic!("{} with unit variant", err),
+        Fields::Named(_) => panic!("{} with named variant", err),
+    }
+}
@@ -0,0 +1,105 @@
+use crate::attr::parse_items;
+use proc_macro2::Span;
+use syn::Attribute;
+use syn::Ident;
+use syn::Meta;
+use syn::NestedMeta;
+use syn::Result;
+use syn::Visibility;
+
+/// Crate-wide options of `#[roset(..)]`, accepted by every derive
+const ROSET_ATTRIBUTES: &[&str] = &[
+    "sort_str_match",
+    "gen_tests",
+    "cfg",
+    "dump",
+    "report",
+    "error_name",
+    "set_name",
+    "variant_error_name",
+    "repr_error_name",
+    "kind_name",
+    "map_name",
+    "migrate_error_name",
+    "static_set_name",
+    "ref_name",
+    "sealed",
+    "sealed_name",
+    "manifest",
+    "vis",
+];
+
+/// Integer types of `#[repr(..)]` and typed values like `enum_value(u8 = 1)`
+pub(crate) const REPR_TYPES: [&str; 12] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+/// `#[roset(..)]` on the enum, parsed the same by every derive, the last option wins
+#[derive(Default)]
+pub(crate) struct RosetOptions {
+    pub sort_str_match: bool,
+    /// Span of `gen_tests`, errors of the test module point at it
+    pub gen_tests: Option<Span>,
+    /// Predicate gating all generated code
+    pub cfg: Option<NestedMeta>,
+    pub dump: bool,
+    pub report: bool,
+    /// Roset-core traits implemented through a sealed trait of the enum
+    pub sealed: bool,
+    /// JSON description of the variants in `ROSET_MANIFEST`
+    pub manifest: bool,
+    /// Visibility of generated methods instead of the enum one
+    pub vis: Option<Visibility>,
+    /// Generated types renamed by options like `error_name`
+    names: Vec<(String, Ident)>,
+}
+
+impl RosetOptions {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut options = RosetOptions::default();
+        for item in parse_items("roset", attrs, ROSET_ATTRIBUTES)? {
+            let key = item.key.to_string();
+            match key.as_str() {
+                "sort_str_match" => item.flag().map(|_| options.sort_str_match = true)?,
+                "gen_tests" => item.flag().map(|_| options.gen_tests = Some(item.span()))?,
+                "dump" => item.flag().map(|_| options.dump = true)?,
+                "report" => item.flag().map(|_| options.report = true)?,
+                "sealed" => item.flag().map(|_| options.sealed = true)?,
+                "manifest" => item.flag().map(|_| options.manifest = true)?,
+                "cfg" => options.cfg = Some(item.str_value()?.parse()?),
+                "vis" => options.vis = Some(item.str_value()?.parse()?),
+                _ => options.names.push((key, item.str_value()?.parse()?)),
+            }
+        }
+        Ok(options)
+    }
+
+    /// Name of a generated type, renamed by the option `key` or `default`
+    pub fn name(&self, key: &str, default: Ident) -> Ident {
+        debug_assert!(ROSET_ATTRIBUTES.contains(&key));
+        match self.names.iter().rev().find(|(name, _)| name == key) {
+            Some((_, ident)) => ident.clone(),
+            None => default,
+        }
+    }
+}
+
+/// Integer type of `#[repr(u8)]` like attributes on the enum, if any
+pub(crate) fn parse_int_repr(attrs: &[Attribute]) -> Result<Option<Ident>> {
+    let mut repr = None;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("repr")) {
+        if let Meta::List(list) = attr.parse_meta()? {
+            for nested in list.nested.iter() {
+                match nested {
+                    NestedMeta::Meta(Meta::Path(path))
+                        if REPR_TYPES.iter().any(|ty| path.is_ident(ty)) =>
+                    {
+                        repr = path.get_ident().cloned();
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(repr)
+}